@@ -1,7 +1,11 @@
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use openbabel::fingerprint::Kind;
+use similarity_metrics::tokenize::{self, Tokenizer};
 use similarity_metrics::{dist, load, measures};
 
+type EditDistance = fn(&str, &str) -> usize;
+type TokenDistance = fn(&[&str], &[&str]) -> usize;
+
 fn run_metrics<T>(metric: fn(&[u8], &[u8]) -> T, fps: Vec<Vec<u8>>) -> Vec<T> {
     fps.iter()
         .flat_map(|x1| {
@@ -22,6 +26,16 @@ fn run_metrics_selfies(metric: fn(&str, &str) -> usize, fps: Vec<String>) -> Vec
         .collect()
 }
 
+fn run_metrics_tokens(metric: TokenDistance, fps: Vec<Vec<&str>>) -> Vec<usize> {
+    fps.iter()
+        .flat_map(|x1| {
+            fps.iter()
+                .filter(move |x2| &x1 != x2)
+                .map(move |x2| metric(x1, x2))
+        })
+        .collect()
+}
+
 fn bench<T>(metric: fn(&[u8], &[u8]) -> T, fp: Kind) -> Vec<T> {
     let fps = load::gen_fps(fp, "test.mol");
 
@@ -34,6 +48,18 @@ fn bench_selfies(metric: fn(&str, &str) -> usize) -> Vec<usize> {
     run_metrics_selfies(metric, fps)
 }
 
+fn bench_pairwise(metric: EditDistance) -> ndarray::Array2<usize> {
+    let fps = load::load_plain("test.mol");
+
+    dist::pairwise(&fps, metric)
+}
+
+fn bench_tokens(metric: TokenDistance, tokenizer: Tokenizer) -> Vec<usize> {
+    let fps = load::load_plain("test.mol");
+
+    run_metrics_tokens(metric, fps.iter().map(|x| tokenizer(x)).collect())
+}
+
 pub fn criterion_benchmark(c: &mut Criterion) {
     let mut group = c.benchmark_group("Similarity Metrics");
     let fps = vec![
@@ -72,13 +98,30 @@ pub fn criterion_benchmark(c: &mut Criterion) {
         });
     }
 
-    let edit_distances: Vec<(&str, fn(&str, &str) -> usize)> =
+    let edit_distances: Vec<(&str, EditDistance)> =
         vec![("LCS", dist::lcs), ("Levenshtein", dist::l_distance)];
 
-    for distance in edit_distances {
+    for distance in &edit_distances {
         group.bench_function(distance.0, |b| b.iter(|| bench_selfies(distance.1)));
     }
 
+    for distance in &edit_distances {
+        group.bench_function(format!("{} (pairwise matrix)", distance.0), |b| {
+            b.iter(|| bench_pairwise(distance.1))
+        });
+    }
+
+    let token_distances: Vec<(&str, TokenDistance)> = vec![
+        ("LCS (SMILES tokens)", dist::lcs_tokens),
+        ("Levenshtein (SMILES tokens)", dist::l_distance_tokens),
+    ];
+
+    for distance in token_distances {
+        group.bench_function(distance.0, |b| {
+            b.iter(|| bench_tokens(distance.1, tokenize::smiles))
+        });
+    }
+
     group.finish();
 }
 
@@ -1,26 +1,141 @@
-use crate::measures::euclidean;
-use ndarray::arr2;
-use petal_clustering::{Fit, Optics};
-use petal_neighbors::distance::Euclidean;
-use rand::seq::SliceRandom;
+use crate::dist::_pairwise;
+use crate::index::VpTree;
+use crate::measures::soergel;
+use ndarray::{Array2, ArrayView1};
+use rand::seq::index::sample;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 
+mod butina;
+mod density;
+mod hierarchy;
+mod jarvis_patrick;
+mod leader;
+mod medoids;
+mod validation;
+
+use butina::popcount;
+pub use butina::{butina, euclidean_bound, soergel_bound, Butina, PopcountBound};
+pub use density::{dbscan, hdbscan};
+pub use hierarchy::{hierarchical, Dendrogram, Linkage, Merge};
+pub use jarvis_patrick::{jarvis_patrick, knn};
+pub use leader::{leader, Leaders};
+pub use medoids::{fasterpam, KMedoids, Medoids};
+pub use validation::{
+    adjusted_rand_index, calinski_harabasz, davies_bouldin, normalized_mutual_info, silhouette,
+    silhouette_sampled,
+};
+
+/// Distance between two fingerprints
+///
+/// Every clustering and picking routine assumes smaller values mean more similar
+/// fingerprints, with 0 for identical ones. Similarities such as `measures::tanimoto`,
+/// `measures::dice` or `measures::cosine` would silently invert every comparison;
+/// pass their distance counterparts (`measures::tanimoto_distance` etc.) instead.
+pub type Metric = fn(&[u8], &[u8]) -> f64;
+
+/// Metric used when none is given: Soergel distance, i.e. 1 - Tanimoto on binary fingerprints
+pub const DEFAULT_METRIC: Metric = soergel;
+
+/// Seed used by randomised routines when none is given
+pub const DEFAULT_SEED: u64 = 0;
+
+/// Random number generator for `seed`, giving the same stream on every platform
+pub fn seeded_rng(seed: u64) -> ChaCha8Rng {
+    ChaCha8Rng::seed_from_u64(seed)
+}
+
+/// Adapter exposing a fingerprint `Metric` as a `petal_neighbors` metric over rows of
+/// 0.0/1.0 values, so fingerprint matrices can be fed to `petal_clustering`
+///
+/// Values are rounded to the nearest bit before the metric is applied.
+///
+/// ## Examples
+///
+/// ```
+/// use ndarray::arr1;
+/// use petal_neighbors::distance::Metric;
+/// use similarity_metrics::cluster::Measure;
+/// use similarity_metrics::measures::soergel;
+/// let metric = Measure(soergel);
+/// let (a, b) = (arr1(&[1.0, 1.0, 0.0, 0.0]), arr1(&[1.0, 0.0, 1.0, 0.0]));
+/// assert_eq!(metric.distance(&a.view(), &b.view()), 2.0 / 3.0);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Measure(pub Metric);
+
+impl petal_neighbors::distance::Metric<f64> for Measure {
+    fn distance(&self, x1: &ArrayView1<f64>, x2: &ArrayView1<f64>) -> f64 {
+        let bits = |x: &ArrayView1<f64>| x.iter().map(|v| v.round() as u8).collect::<Vec<_>>();
+        (self.0)(&bits(x1), &bits(x2))
+    }
+
+    fn rdistance(&self, x1: &ArrayView1<f64>, x2: &ArrayView1<f64>) -> f64 {
+        self.distance(x1, x2)
+    }
+
+    fn rdistance_to_distance(&self, d: f64) -> f64 {
+        d
+    }
+
+    fn distance_to_rdistance(&self, d: f64) -> f64 {
+        d
+    }
+}
+
+/// Data bubble summarising a group of fingerprints, held as indices into a shared set
+/// so that no fingerprint is copied
 #[derive(Clone)]
-pub struct Bubble {
-    objects: Vec<Vec<u8>>,
+pub struct Bubble<'a> {
+    fps: Cow<'a, [Vec<u8>]>,
+    members: Vec<usize>,
+}
+
+impl Bubble<'static> {
+    /// Bubble owning all of `objects`
+    pub fn new(objects: Vec<Vec<u8>>) -> Self {
+        Bubble {
+            members: (0..objects.len()).collect(),
+            fps: Cow::Owned(objects),
+        }
+    }
 }
 
-impl Bubble {
+impl<'a> Bubble<'a> {
+    /// Bubble of the fingerprints at `members` in `fps`
+    pub fn of(fps: &'a [Vec<u8>], members: Vec<usize>) -> Self {
+        Bubble {
+            fps: Cow::Borrowed(fps),
+            members,
+        }
+    }
+
+    /// Indices of the objects in the set of fingerprints the bubble was built on
+    pub fn members(&self) -> &[usize] {
+        &self.members
+    }
+
+    fn objects(&self) -> impl Iterator<Item = &[u8]> {
+        self.members.iter().map(|&i| &self.fps[i][..])
+    }
+
+    fn object(&self, i: usize) -> &[u8] {
+        &self.fps[self.members[i]]
+    }
+
     pub fn n(&self) -> usize {
-        self.objects.len()
+        self.members.len()
     }
 
     pub fn d(&self) -> usize {
-        self.objects[0].len()
+        self.object(0).len()
     }
 
     pub fn rep(&self) -> Vec<f64> {
-        self.objects
-            .iter()
+        self.objects()
             .fold(vec![0.0; self.d()], |acc, i| {
                 acc.iter()
                     .zip(i.iter())
@@ -32,68 +147,758 @@ impl Bubble {
             .collect()
     }
 
-    pub fn extent(&self) -> f64 {
-        (self.objects.iter().fold(0.0, |acc, i| {
-            acc + self.objects.iter().fold(0.0, |inner_acc, j| {
-                inner_acc
-                    + i.iter()
-                        .zip(j.iter())
-                        .map(|(a, b)| a - b)
-                        .fold(0, |squaresum, x| squaresum + (x ^ 2)) as f64
+    /// Total distance from each object to all the others,
+    /// and the sum of squared distances over all pairs
+    fn distance_sums(&self, metric: Metric) -> (Vec<f64>, f64) {
+        let mut totals = vec![0.0; self.n()];
+        let mut squares = 0.0;
+
+        for (i, a) in self.objects().enumerate() {
+            for (j, b) in self.objects().enumerate().skip(i + 1) {
+                let d = metric(a, b);
+                totals[i] += d;
+                totals[j] += d;
+                squares += d * d;
+            }
+        }
+
+        (totals, squares)
+    }
+
+    /// Root-mean-square distance between every pair of objects in the bubble,
+    /// as defined for data bubbles by Breunig et al.; 0 for bubbles with fewer
+    /// than two objects
+    pub fn extent(&self, metric: Metric) -> f64 {
+        rms(self.n(), self.distance_sums(metric).1)
+    }
+
+    /// Object with the smallest total distance to the others, the first one on ties
+    pub fn medoid(&self, metric: Metric) -> &[u8] {
+        let (totals, _) = self.distance_sums(metric);
+        self.object(argmin(&totals))
+    }
+
+    /// Expected distance from an object in the bubble to its `k`-th nearest neighbour,
+    /// assuming the objects are uniformly distributed within the bubble's extent
+    pub fn nn_dist(&self, k: usize, metric: Metric) -> f64 {
+        expected_nn_dist(k, self.n(), self.d(), self.extent(metric))
+    }
+}
+
+/// Root-mean-square pairwise distance of `n` objects from the sum of squared distances
+fn rms(n: usize, squares: f64) -> f64 {
+    if n < 2 {
+        0.0
+    } else {
+        (2.0 * squares / (n * (n - 1)) as f64).sqrt()
+    }
+}
+
+/// Length shared by all fingerprints, or 0 if there are none
+///
+/// Panics if the fingerprints have different lengths.
+fn fingerprint_len(fps: &[Vec<u8>]) -> usize {
+    let len = fps.first().map_or(0, Vec::len);
+    assert!(
+        fps.iter().all(|fp| fp.len() == len),
+        "Expected fingerprints to have same length."
+    );
+    len
+}
+
+/// Symmetric matrix of `metric` between every pair of fingerprints, computed in parallel
+///
+/// Panics if the fingerprints have different lengths.
+pub fn distance_matrix(fps: &[Vec<u8>], metric: Metric) -> Array2<f64> {
+    fingerprint_len(fps);
+    _pairwise(fps, |a, b| metric(a, b))
+}
+
+/// Union-find over `0..n` with path halving
+struct DisjointSets {
+    parent: Vec<usize>,
+}
+
+impl DisjointSets {
+    fn new(n: usize) -> Self {
+        DisjointSets {
+            parent: (0..n).collect(),
+        }
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    /// Join the sets of `a` and `b`, returning the root of the joined set
+    fn union(&mut self, a: usize, b: usize) -> usize {
+        let (a, b) = (self.find(a), self.find(b));
+        self.parent[b] = a;
+        a
+    }
+
+    /// Label of every element's set, numbered by the first element of each set
+    fn labels(&mut self) -> Vec<Option<usize>> {
+        let mut numbers = vec![None; self.parent.len()];
+        let mut count = 0;
+        (0..self.parent.len())
+            .map(|i| {
+                let root = self.find(i);
+                *numbers[root].get_or_insert_with(|| {
+                    count += 1;
+                    count - 1
+                })
             })
-        }) / (self.n() * (self.n() - 1)) as f64)
-            .sqrt()
+            .map(Some)
+            .collect()
+    }
+}
+
+/// Index of the smallest value, the first one on ties
+fn argmin(values: &[f64]) -> usize {
+    values
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map_or(0, |(i, _)| i)
+}
+
+fn expected_nn_dist(k: usize, n: usize, d: usize, extent: f64) -> f64 {
+    (k as f64 / n as f64).powf(1.0 / d as f64) * extent
+}
+
+/// Medoid, size, dimension and extent of a non-empty bubble, computed once for OPTICS
+struct Summary {
+    medoid: Vec<u8>,
+    n: usize,
+    d: usize,
+    extent: f64,
+    metric: Metric,
+}
+
+impl Summary {
+    fn new(bubble: &Bubble, metric: Metric) -> Self {
+        let (totals, squares) = bubble.distance_sums(metric);
+        Summary {
+            medoid: bubble.object(argmin(&totals)).to_vec(),
+            n: bubble.n(),
+            d: bubble.d(),
+            extent: rms(bubble.n(), squares),
+            metric,
+        }
     }
 
-    pub fn nn_dist(&self, k: usize) -> f64 {
-        (k as f64 / self.n() as f64).powf(1.0 / (self.d() as f64))
+    fn nn_dist(&self, k: usize) -> f64 {
+        expected_nn_dist(k, self.n, self.d, self.extent)
+    }
+
+    /// Distance between two bubbles: the gap between their extents around the medoids
+    /// plus the expected nearest-neighbour distance on either side, or the larger
+    /// nearest-neighbour distance if they overlap
+    fn distance(&self, other: &Summary) -> f64 {
+        let centres = (self.metric)(&self.medoid, &other.medoid);
+        let gap = centres - (self.extent + other.extent);
+
+        if gap >= 0.0 {
+            gap + self.nn_dist(1) + other.nn_dist(1)
+        } else {
+            self.nn_dist(1).max(other.nn_dist(1))
+        }
     }
 }
 
-pub fn compute_bubbles(x: Vec<Vec<u8>>, k: usize) -> Vec<Bubble> {
-    let sample_objects: Vec<Vec<u8>> = x
-        .choose_multiple(&mut rand::thread_rng(), k)
-        .cloned()
-        .collect();
+/// Distance within which `min_pts` objects are expected around a bubble,
+/// given its `distances` to every bubble; infinite if fewer than `min_pts` objects
+/// lie within `eps`
+fn core_distance(distances: &[f64], summaries: &[Summary], eps: f64, min_pts: usize) -> f64 {
+    let mut neighbours = (0..summaries.len())
+        .filter(|&c| distances[c] <= eps)
+        .collect::<Vec<_>>();
+    neighbours.sort_by(|&a, &b| distances[a].total_cmp(&distances[b]).then(a.cmp(&b)));
+
+    let mut count = 0;
+    for c in neighbours {
+        if count + summaries[c].n >= min_pts {
+            return distances[c] + summaries[c].nn_dist(min_pts - count);
+        }
+        count += summaries[c].n;
+    }
+    f64::INFINITY
+}
+
+/// OPTICS cluster ordering with the reachability and core distance of each item,
+/// both infinite where undefined
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReachabilityPlot {
+    pub order: Vec<usize>,
+    pub reachability: Vec<f64>,
+    pub core_distance: Vec<f64>,
+}
+
+impl ReachabilityPlot {
+    /// Clusters found by cutting the plot at `eps`, which must not exceed the `eps` used
+    /// to build it, as lists of item indices, together with the items left as noise
+    pub fn extract(&self, eps: f64) -> (Vec<Vec<usize>>, Vec<usize>) {
+        let mut clusters: Vec<Vec<usize>> = Vec::new();
+        let mut noise = Vec::new();
+
+        for (i, &item) in self.order.iter().enumerate() {
+            if self.reachability[i] <= eps {
+                match clusters.last_mut() {
+                    Some(cluster) => cluster.push(item),
+                    None => noise.push(item),
+                }
+            } else if self.core_distance[i] <= eps {
+                clusters.push(vec![item]);
+            } else {
+                noise.push(item);
+            }
+        }
 
-    x.iter()
-        .fold(vec![Bubble { objects: vec![] }; k], |mut acc, a| {
-            acc[sample_objects
-                .iter()
-                .map(|x| euclidean(x, a))
-                .enumerate()
-                .min_by(|(_, a), (_, b)| a.total_cmp(b))
-                .unwrap()
-                .0]
-                .objects
-                .push(a.clone());
+        (clusters, noise)
+    }
+
+    /// Clusters found with the Xi method of Ankerst et al., as in scikit-learn without
+    /// predecessor correction, as lists of item indices together with the noise
+    ///
+    /// A cluster starts in an area where reachability falls by a factor of at least
+    /// `1 - xi` between neighbouring items and ends in an area where it rises as steeply;
+    /// steep areas may contain up to `min_pts` consecutive less steep items, and clusters
+    /// need at least `min_pts` items. Of nested clusters only the innermost are kept.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use similarity_metrics::cluster::ReachabilityPlot;
+    /// let inf = f64::INFINITY;
+    /// let plot = ReachabilityPlot {
+    ///     order: (0..8).collect(),
+    ///     reachability: vec![inf, 0.1, 0.1, 0.1, 0.9, 0.1, 0.1, 0.1],
+    ///     core_distance: vec![0.1; 8],
+    /// };
+    /// let (clusters, noise) = plot.extract_xi(0.5, 2);
+    /// assert_eq!(clusters, [vec![0, 1, 2, 3], vec![4, 5, 6, 7]]);
+    /// assert!(noise.is_empty());
+    /// ```
+    pub fn extract_xi(&self, xi: f64, min_pts: usize) -> (Vec<Vec<usize>>, Vec<usize>) {
+        let n = self.order.len();
+        // Reachability with a final infinite value, so every cluster can end
+        let r = |i: usize| self.reachability.get(i).copied().unwrap_or(f64::INFINITY);
+        let complement = 1.0 - xi;
+        let ratio = (0..n).map(|i| r(i) / r(i + 1)).collect::<Vec<_>>();
+        let steep_up = ratio.iter().map(|&q| q <= complement).collect::<Vec<_>>();
+        let steep_down = ratio
+            .iter()
+            .map(|&q| q >= 1.0 / complement)
+            .collect::<Vec<_>>();
+        let up = ratio.iter().map(|&q| q < 1.0).collect::<Vec<_>>();
+        let down = ratio.iter().map(|&q| q > 1.0).collect::<Vec<_>>();
+
+        // Last item of the steep area from `start`, allowing up to `min_pts` consecutive
+        // items that are not steep but still head the same way
+        let extend = |steep: &[bool], same_way: &[bool], start: usize| {
+            let mut end = start;
+            let mut gentle = 0;
+            for i in start..n {
+                if steep[i] {
+                    gentle = 0;
+                    end = i;
+                } else if !same_way[i] {
+                    gentle += 1;
+                    if gentle > min_pts {
+                        break;
+                    }
+                } else {
+                    break;
+                }
+            }
+            end
+        };
+
+        struct SteepDown {
+            start: usize,
+            end: usize,
+            mib: f64,
+        }
+        // Keep the steep-down areas still deep enough below the maximum in between
+        let filter = |areas: &mut Vec<SteepDown>, mib: f64| {
+            if mib.is_infinite() {
+                areas.clear();
+            }
+            areas.retain(|a| mib <= r(a.start) * complement);
+            for a in areas.iter_mut() {
+                a.mib = a.mib.max(mib);
+            }
+        };
+
+        let mut areas: Vec<SteepDown> = Vec::new();
+        let mut ranges = Vec::new();
+        let mut index = 0;
+        let mut mib = 0.0f64;
+        for steep in (0..n).filter(|&i| steep_up[i] || steep_down[i]) {
+            if steep < index {
+                continue;
+            }
+            mib = (index..=steep).map(r).fold(mib, f64::max);
+            filter(&mut areas, mib);
+
+            if steep_down[steep] {
+                let end = extend(&steep_down, &up, steep);
+                areas.push(SteepDown {
+                    start: steep,
+                    end,
+                    mib: 0.0,
+                });
+                index = end + 1;
+                mib = r(index);
+                continue;
+            }
+
+            let up_end = extend(&steep_up, &down, steep);
+            index = up_end + 1;
+            mib = r(index);
+            let mut found = Vec::new();
+            for a in &areas {
+                let (mut start, mut end) = (a.start, up_end);
+                if r(end + 1) * complement < a.mib {
+                    continue;
+                }
+
+                let top = r(a.start);
+                if top * complement >= r(end + 1) {
+                    while r(start + 1) > r(end + 1) && start < a.end {
+                        start += 1;
+                    }
+                } else if r(end + 1) * complement >= top {
+                    while end > steep && r(end - 1) > top {
+                        end -= 1;
+                    }
+                }
+
+                if end + 1 - start >= min_pts && start <= a.end && end >= steep {
+                    found.push((start, end));
+                }
+            }
+            // Inner clusters first
+            ranges.extend(found.into_iter().rev());
+        }
+
+        let mut labels = vec![None; n];
+        let mut clusters: Vec<Vec<usize>> = Vec::new();
+        for (start, end) in ranges {
+            if labels[start..=end].iter().all(Option::is_none) {
+                labels[start..=end].fill(Some(clusters.len()));
+                clusters.push(self.order[start..=end].to_vec());
+            }
+        }
+        let noise = (0..n)
+            .filter(|&i| labels[i].is_none())
+            .map(|i| self.order[i])
+            .collect();
+
+        (clusters, noise)
+    }
+}
+
+/// Clustering of a set of objects, identified by their index in the input
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClusterResult {
+    /// Cluster of every object, `None` for noise
+    pub labels: Vec<Option<usize>>,
+    /// Indices of the members of each cluster, in ascending order
+    pub clusters: Vec<Vec<usize>>,
+    /// Indices of the objects in no cluster, in ascending order
+    pub noise: Vec<usize>,
+    /// Bubble of every object, if the objects were summarised by data bubbles
+    pub bubbles: Option<Vec<usize>>,
+    /// OPTICS ordering and reachability over the objects, if clustered with OPTICS
+    pub plot: Option<ReachabilityPlot>,
+}
+
+impl ClusterResult {
+    /// Result with the given label for every object, numbered from 0
+    pub fn from_labels(labels: Vec<Option<usize>>) -> Self {
+        let count = labels.iter().flatten().map(|&c| c + 1).max().unwrap_or(0);
+        let mut clusters = vec![Vec::new(); count];
+        let mut noise = Vec::new();
+        for (i, label) in labels.iter().enumerate() {
+            match label {
+                Some(c) => clusters[*c].push(i),
+                None => noise.push(i),
+            }
+        }
+
+        ClusterResult {
+            labels,
+            clusters,
+            noise,
+            bubbles: None,
+            plot: None,
+        }
+    }
+
+    /// Result with the given clusters of object indices out of `n` objects,
+    /// leaving the rest as noise
+    pub fn from_clusters(n: usize, clusters: &[Vec<usize>]) -> Self {
+        let mut labels = vec![None; n];
+        for (c, members) in clusters.iter().enumerate() {
+            for &i in members {
+                labels[i] = Some(c);
+            }
+        }
+        ClusterResult::from_labels(labels)
+    }
+
+    pub fn n_clusters(&self) -> usize {
+        self.clusters.len()
+    }
+
+    /// Number of members of each cluster
+    pub fn sizes(&self) -> Vec<usize> {
+        self.clusters.iter().map(Vec::len).collect()
+    }
+}
+
+/// Cluster around a centroid, which is an actual member
+#[derive(Debug, Clone, PartialEq)]
+pub struct CentroidCluster {
+    pub centroid: usize,
+    /// Indices of all members, starting with the centroid
+    pub members: Vec<usize>,
+}
+
+/// Clusters of two or more members and the indices left on their own
+#[derive(Debug, Clone, PartialEq)]
+pub struct CentroidClustering {
+    pub clusters: Vec<CentroidCluster>,
+    pub singletons: Vec<usize>,
+}
+
+/// Data-bubble OPTICS (Breunig et al.) over non-empty `bubbles`
+///
+/// Distances between bubbles account for their extents and expected nearest-neighbour
+/// distances, and the core distance of a bubble counts the objects of its neighbours,
+/// so each bubble stands in for all of its objects. Bubbles are placed at their medoids,
+/// so any `metric` applies. The plot is over bubble indices.
+pub fn optics(bubbles: &[Bubble], eps: f64, min_pts: usize, metric: Metric) -> ReachabilityPlot {
+    let summaries = bubbles
+        .par_iter()
+        .map(|b| Summary::new(b, metric))
+        .collect::<Vec<_>>();
+    let m = summaries.len();
+
+    let mut reachability = vec![f64::INFINITY; m];
+    let mut cores = vec![f64::INFINITY; m];
+    let mut processed = vec![false; m];
+    let mut order = Vec::with_capacity(m);
+
+    for start in 0..m {
+        let mut next = (!processed[start]).then_some(start);
+
+        while let Some(b) = next {
+            processed[b] = true;
+            order.push(b);
+
+            let mut distances = summaries
+                .par_iter()
+                .map(|c| summaries[b].distance(c))
+                .collect::<Vec<_>>();
+            distances[b] = 0.0;
+            cores[b] = core_distance(&distances, &summaries, eps, min_pts);
+
+            if cores[b].is_finite() {
+                for c in (0..m).filter(|&c| !processed[c] && distances[c] <= eps) {
+                    reachability[c] = reachability[c].min(cores[b].max(distances[c]));
+                }
+            }
+
+            next = (0..m)
+                .filter(|&c| !processed[c] && reachability[c].is_finite())
+                .min_by(|&a, &c| reachability[a].total_cmp(&reachability[c]));
+        }
+    }
+
+    ReachabilityPlot {
+        reachability: order.iter().map(|&b| reachability[b]).collect(),
+        core_distance: order.iter().map(|&b| cores[b]).collect(),
+        order,
+    }
+}
+
+/// How every object finds the nearest of the bubble seeds
+#[derive(Debug, Clone, Copy)]
+pub enum SeedIndex {
+    /// Compare every object with every seed
+    Exhaustive,
+    /// Search a vantage-point tree over the seeds; exact only for metrics that obey
+    /// the triangle inequality, such as `soergel` and `euclidean`
+    VpTree,
+    /// Scan the seeds in buckets of equal popcount, starting from the object's own
+    /// popcount, until the bound rules out every remaining bucket
+    Popcount(PopcountBound),
+}
+
+/// Fingerprint compared under its metric, so seeds can be indexed in a `VpTree`
+struct Point<'a> {
+    fp: &'a [u8],
+    metric: Metric,
+}
+
+impl Point<'_> {
+    fn distance(a: &Point, b: &Point) -> f64 {
+        (a.metric)(a.fp, b.fp)
+    }
+}
+
+/// Nearest of `k` randomly chosen seed objects for every object in `x`, as a position
+/// in the list of seeds, the first one on ties; all objects are seeds if there are
+/// fewer than `k`
+///
+/// Panics if `k` is 0 and `x` is not empty.
+fn assign<R: Rng>(
+    x: &[Vec<u8>],
+    k: usize,
+    metric: Metric,
+    index: SeedIndex,
+    rng: &mut R,
+) -> Vec<usize> {
+    assert!(k > 0 || x.is_empty(), "Expected at least one bubble.");
+    let seeds = sample(rng, x.len(), k.min(x.len())).into_vec();
+
+    match index {
+        SeedIndex::Exhaustive => x
+            .par_iter()
+            .map(|a| argmin(&seeds.iter().map(|&s| metric(&x[s], a)).collect::<Vec<_>>()))
+            .collect(),
+        SeedIndex::VpTree => {
+            let tree = VpTree::new(
+                seeds.iter().map(|&s| Point { fp: &x[s], metric }).collect(),
+                Point::distance,
+            );
+            x.par_iter()
+                .map(|a| tree.nearest(&Point { fp: a, metric }, 1)[0].0)
+                .collect()
+        }
+        SeedIndex::Popcount(bound) => {
+            let mut by_popcount = BTreeMap::new();
+            for (position, &s) in seeds.iter().enumerate() {
+                by_popcount
+                    .entry(popcount(&x[s]))
+                    .or_insert_with(Vec::new)
+                    .push(position);
+            }
+            let buckets = by_popcount.into_iter().collect::<Vec<_>>();
+
+            x.par_iter()
+                .map(|a| {
+                    let count = popcount(a);
+                    // Buckets below `down` and from `up` on are still to be scanned
+                    let mut down = buckets.partition_point(|&(c, _)| c < count);
+                    let mut up = down;
+                    let mut best = (f64::INFINITY, 0);
+                    loop {
+                        let below = down.checked_sub(1).map(|b| bound(buckets[b].0, count));
+                        let above = buckets.get(up).map(|&(c, _)| bound(c, count));
+                        let (limit, bucket) = match (below, above) {
+                            (Some(l), Some(u)) if l <= u => (l, down - 1),
+                            (Some(l), None) => (l, down - 1),
+                            (_, Some(u)) => (u, up),
+                            (None, None) => break,
+                        };
+                        if limit > best.0 {
+                            break;
+                        }
+                        if bucket < down {
+                            down -= 1;
+                        } else {
+                            up += 1;
+                        }
+
+                        for &position in &buckets[bucket].1 {
+                            let d = metric(&x[seeds[position]], a);
+                            if d < best.0 || (d == best.0 && position < best.1) {
+                                best = (d, position);
+                            }
+                        }
+                    }
+                    best.1
+                })
+                .collect()
+        }
+    }
+}
+
+/// Group objects by bubble, as lists of object indices in ascending order,
+/// leaving out bubbles that no object was assigned to
+fn members(labels: &[usize]) -> Vec<Vec<usize>> {
+    let k = labels.iter().max().map_or(0, |&b| b + 1);
+    labels
+        .iter()
+        .enumerate()
+        .fold(vec![vec![]; k], |mut acc, (i, &b)| {
+            acc[b].push(i);
             acc
         })
+        .into_iter()
+        .filter(|m| !m.is_empty())
+        .collect()
 }
 
-pub fn cluster(x: Vec<Vec<u8>>, k: usize, tolerance: f64, min_pts: usize) -> Vec<Vec<Vec<u8>>> {
-    println!("== Computing Bubbles");
-    let bubbles: Vec<Bubble> = compute_bubbles(x.clone(), k)
-        .iter()
-        .filter(|x| !x.objects.is_empty())
-        .cloned()
-        .collect();
-    println!("== Finding representatives");
-    let rep = arr2(
-        bubbles
-            .iter()
-            .map(|x| {
-                x.rep().try_into().unwrap()
-            })
-            .collect::<Vec<[f64; 64]>>()
-            .as_slice(),
-    );
-    println!("== Clustering");
-    let clustering = Optics::new(tolerance, min_pts, Euclidean::default()).fit(&rep, None);
+/// Split `x` into at most `k` bubbles around random seeds, using `DEFAULT_METRIC` and `DEFAULT_SEED`
+/// and comparing every object with every seed
+pub fn compute_bubbles(x: &[Vec<u8>], k: usize) -> Vec<Bubble<'_>> {
+    compute_bubbles_by(
+        x,
+        k,
+        DEFAULT_METRIC,
+        SeedIndex::Exhaustive,
+        &mut seeded_rng(DEFAULT_SEED),
+    )
+}
 
-    clustering
-        .0
-        .values()
-        .map(|i| i.iter().flat_map(|j| bubbles[*j].objects.clone()).collect())
+/// Split `x` into at most `k` bubbles, assigning every object in parallel to its nearest seed
+/// under `metric`, found through `index`, with the seeds drawn from `rng`
+///
+/// The bubbles hold indices into `x` rather than copies of the fingerprints. Every
+/// bubble is non-empty, so there are fewer than `k` if `x` has fewer than `k` objects
+/// or some seeds are identical. Panics if `k` is 0 and `x` is not empty.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::cluster::{compute_bubbles_by, seeded_rng, soergel_bound, SeedIndex};
+/// use similarity_metrics::measures::soergel;
+/// let fps = vec![vec![1, 1, 0, 0], vec![1, 1, 1, 0], vec![0, 0, 1, 1], vec![0, 1, 1, 1]];
+/// let bubbles = compute_bubbles_by(
+///     &fps,
+///     2,
+///     soergel,
+///     SeedIndex::Popcount(soergel_bound),
+///     &mut seeded_rng(0),
+/// );
+/// let mut members = bubbles.iter().map(|b| b.members().to_vec()).collect::<Vec<_>>();
+/// members.sort();
+/// assert_eq!(members.concat().len(), fps.len());
+/// ```
+pub fn compute_bubbles_by<'a, R: Rng>(
+    x: &'a [Vec<u8>],
+    k: usize,
+    metric: Metric,
+    index: SeedIndex,
+    rng: &mut R,
+) -> Vec<Bubble<'a>> {
+    members(&assign(x, k, metric, index, rng))
+        .into_iter()
+        .map(|m| Bubble::of(x, m))
         .collect()
 }
+
+/// Data-bubble OPTICS over fingerprints
+///
+/// Objects are assigned to `k` bubbles around seeds drawn with `seed`, so a given
+/// configuration always produces the same clustering of the same input. The same
+/// `metric` assigns objects to bubbles and measures distances between them.
+#[derive(Debug, Clone, Copy)]
+pub struct BubbleOptics {
+    /// Number of bubbles
+    pub k: usize,
+    /// Neighbourhood radius for OPTICS, also used to extract the clusters
+    pub tolerance: f64,
+    pub min_pts: usize,
+    pub metric: Metric,
+    pub seed: u64,
+    /// How objects find their nearest seed
+    pub index: SeedIndex,
+}
+
+impl BubbleOptics {
+    /// Configuration using `DEFAULT_METRIC` and `DEFAULT_SEED`, comparing every object
+    /// with every seed
+    pub fn new(k: usize, tolerance: f64, min_pts: usize) -> Self {
+        BubbleOptics {
+            k,
+            tolerance,
+            min_pts,
+            metric: DEFAULT_METRIC,
+            seed: DEFAULT_SEED,
+            index: SeedIndex::Exhaustive,
+        }
+    }
+
+    /// Returns the clusters found at `tolerance` together with the bubble of every
+    /// object and the reachability plot over the objects. Within each bubble the first
+    /// object takes the bubble's reachability and the rest take the virtual reachability
+    /// of Breunig et al., the expected distance to their `min_pts`-th nearest neighbour
+    /// within the bubble. Every object keeps its bubble's core distance.
+    ///
+    /// Panics if `k` is 0 and `x` is not empty.
+    pub fn fit(&self, x: &[Vec<u8>]) -> ClusterResult {
+        let labels = assign(
+            x,
+            self.k,
+            self.metric,
+            self.index,
+            &mut seeded_rng(self.seed),
+        );
+        let members = members(&labels);
+        let mut bubble_of = vec![0; x.len()];
+        for (b, m) in members.iter().enumerate() {
+            for &i in m {
+                bubble_of[i] = b;
+            }
+        }
+        let bubbles: Vec<Bubble> = members.iter().map(|m| Bubble::of(x, m.clone())).collect();
+        let bubble_plot = optics(&bubbles, self.tolerance, self.min_pts, self.metric);
+        let virtual_reachability = bubbles
+            .par_iter()
+            .map(|b| b.nn_dist(self.min_pts, self.metric))
+            .collect::<Vec<_>>();
+
+        let mut plot = ReachabilityPlot {
+            order: Vec::with_capacity(x.len()),
+            reachability: Vec::with_capacity(x.len()),
+            core_distance: Vec::with_capacity(x.len()),
+        };
+        for (i, &b) in bubble_plot.order.iter().enumerate() {
+            let core = bubble_plot.core_distance[i];
+            plot.order.extend(&members[b]);
+            plot.reachability.push(bubble_plot.reachability[i]);
+            plot.reachability
+                .extend(vec![virtual_reachability[b]; members[b].len() - 1]);
+            plot.core_distance.extend(vec![core; members[b].len()]);
+        }
+
+        let result = ClusterResult::from_clusters(x.len(), &plot.extract(self.tolerance).0);
+        ClusterResult {
+            bubbles: Some(bubble_of),
+            plot: Some(plot),
+            ..result
+        }
+    }
+}
+
+/// Cluster fingerprints with data-bubble OPTICS over `k` bubbles,
+/// using `DEFAULT_METRIC` and `DEFAULT_SEED`
+pub fn cluster(x: Vec<Vec<u8>>, k: usize, tolerance: f64, min_pts: usize) -> ClusterResult {
+    BubbleOptics::new(k, tolerance, min_pts).fit(&x)
+}
+
+/// Cluster fingerprints with data-bubble OPTICS over `k` bubbles under `metric`,
+/// using `DEFAULT_SEED`; see `BubbleOptics`
+pub fn cluster_by(
+    x: Vec<Vec<u8>>,
+    k: usize,
+    tolerance: f64,
+    min_pts: usize,
+    metric: Metric,
+) -> ClusterResult {
+    BubbleOptics {
+        metric,
+        ..BubbleOptics::new(k, tolerance, min_pts)
+    }
+    .fit(&x)
+}
@@ -0,0 +1,215 @@
+use super::{CentroidCluster, CentroidClustering, Metric};
+use crate::measures::soergel;
+use rayon::prelude::*;
+use std::cmp::Reverse;
+
+/// Lower bound on the distance between two fingerprints given only their popcounts
+///
+/// The bound must not decrease as the two popcounts move apart.
+pub type PopcountBound = fn(usize, usize) -> f64;
+
+/// Popcount bound for Soergel (1 - Tanimoto) distance, `1 - min(a, b) / max(a, b)`
+pub fn soergel_bound(a: usize, b: usize) -> f64 {
+    match (a.min(b), a.max(b)) {
+        (_, 0) => 0.0,
+        (lo, hi) => 1.0 - lo as f64 / hi as f64,
+    }
+}
+
+/// Popcount bound for Euclidean distance, `sqrt(|a - b|)`
+pub fn euclidean_bound(a: usize, b: usize) -> f64 {
+    (a.abs_diff(b) as f64).sqrt()
+}
+
+pub(super) fn popcount(fp: &[u8]) -> usize {
+    fp.iter().filter(|&&bit| bit != 0).count()
+}
+
+/// Every fingerprint's neighbours within `threshold`, as `(index, distance)` pairs
+/// sorted by index
+///
+/// Pairs are compared in parallel in order of popcount, and a `bound` stops the scan
+/// as soon as no fingerprint with a larger popcount can be close enough.
+pub(crate) fn radius_neighbours(
+    fps: &[Vec<u8>],
+    threshold: f64,
+    metric: Metric,
+    bound: Option<PopcountBound>,
+) -> Vec<Vec<(usize, f64)>> {
+    let popcounts = fps.iter().map(|fp| popcount(fp)).collect::<Vec<_>>();
+    let mut sorted = (0..fps.len()).collect::<Vec<_>>();
+    sorted.sort_by_key(|&i| (popcounts[i], i));
+
+    let upper = (0..sorted.len())
+        .into_par_iter()
+        .map(|p| {
+            let i = sorted[p];
+            sorted[p + 1..]
+                .iter()
+                .take_while(|&&j| bound.is_none_or(|b| b(popcounts[i], popcounts[j]) <= threshold))
+                .filter_map(|&j| {
+                    let d = metric(&fps[i], &fps[j]);
+                    (d <= threshold).then_some((j, d))
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut neighbours = vec![Vec::new(); fps.len()];
+    for (p, hits) in upper.into_iter().enumerate() {
+        let i = sorted[p];
+        for (j, d) in hits {
+            neighbours[i].push((j, d));
+            neighbours[j].push((i, d));
+        }
+    }
+    for list in &mut neighbours {
+        list.sort_unstable_by_key(|&(j, _)| j);
+    }
+    neighbours
+}
+
+/// Taylor-Butina clustering
+///
+/// Fingerprints within `threshold` of each other are neighbours. Fingerprints are
+/// taken in order of decreasing neighbour count, lower index first on ties; each one
+/// not yet assigned becomes a centroid and takes all its unassigned neighbours.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::cluster::Butina;
+/// let fps = vec![
+///     vec![1, 1, 1, 1, 0, 0, 0, 0],
+///     vec![1, 1, 1, 0, 0, 0, 0, 0],
+///     vec![1, 1, 1, 1, 1, 0, 0, 0],
+///     vec![0, 0, 0, 0, 0, 1, 1, 1],
+///     vec![0, 0, 0, 0, 1, 1, 1, 1],
+///     vec![1, 0, 0, 0, 0, 0, 0, 1],
+/// ];
+/// let result = Butina::tanimoto(0.3).fit(&fps);
+/// assert_eq!(result.clusters.len(), 2);
+/// assert_eq!(result.clusters[0].centroid, 0);
+/// assert_eq!(result.clusters[0].members, [0, 1, 2]);
+/// assert_eq!(result.clusters[1].members, [3, 4]);
+/// assert_eq!(result.singletons, [5]);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Butina {
+    /// Largest distance at which two fingerprints are neighbours
+    pub threshold: f64,
+    pub metric: Metric,
+    /// Popcount bound valid for `metric`, used to skip pairs that cannot be neighbours
+    pub bound: Option<PopcountBound>,
+    /// Move each singleton that has neighbours into the cluster of its nearest neighbour
+    pub reassign_singletons: bool,
+}
+
+impl Butina {
+    pub fn new(threshold: f64, metric: Metric) -> Self {
+        Butina {
+            threshold,
+            metric,
+            bound: None,
+            reassign_singletons: false,
+        }
+    }
+
+    /// Butina clustering on Soergel distance, i.e. neighbours have a Tanimoto
+    /// similarity of at least `1 - threshold`, with popcount pruning
+    pub fn tanimoto(threshold: f64) -> Self {
+        Butina {
+            bound: Some(soergel_bound),
+            ..Butina::new(threshold, soergel)
+        }
+    }
+
+    pub fn fit(&self, fps: &[Vec<u8>]) -> CentroidClustering {
+        let neighbours = radius_neighbours(fps, self.threshold, self.metric, self.bound);
+
+        let mut order = (0..fps.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| (Reverse(neighbours[i].len()), i));
+
+        let mut assigned = vec![false; fps.len()];
+        let mut clusters = Vec::new();
+        let mut singletons = Vec::new();
+        for centroid in order {
+            if assigned[centroid] {
+                continue;
+            }
+            assigned[centroid] = true;
+
+            let mut members = vec![centroid];
+            for &(j, _) in &neighbours[centroid] {
+                if !assigned[j] {
+                    assigned[j] = true;
+                    members.push(j);
+                }
+            }
+
+            if members.len() == 1 {
+                singletons.push(centroid);
+            } else {
+                clusters.push(CentroidCluster { centroid, members });
+            }
+        }
+
+        if self.reassign_singletons {
+            let mut labels = vec![usize::MAX; fps.len()];
+            for (label, cluster) in clusters.iter().enumerate() {
+                for &i in &cluster.members {
+                    labels[i] = label;
+                }
+            }
+
+            // A singleton's neighbours were all taken by earlier, larger clusters
+            singletons.retain(|&s| {
+                let nearest = neighbours[s]
+                    .iter()
+                    .min_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+                match nearest {
+                    Some(&(j, _)) => {
+                        clusters[labels[j]].members.push(s);
+                        false
+                    }
+                    None => true,
+                }
+            });
+        }
+
+        singletons.sort_unstable();
+        CentroidClustering {
+            clusters,
+            singletons,
+        }
+    }
+}
+
+/// Taylor-Butina clustering with neighbours within `threshold` under `metric`
+///
+/// Every pair is compared. To prune pairs by popcount, set a `bound` valid for `metric`
+/// on `Butina`, or use `Butina::tanimoto`; `Butina` also reassigns singletons.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::cluster::{butina, soergel_bound, Butina};
+/// use similarity_metrics::measures::soergel;
+/// let fps = vec![
+///     vec![1, 1, 1, 0, 0, 0],
+///     vec![1, 1, 0, 0, 0, 0],
+///     vec![0, 0, 0, 1, 1, 1],
+/// ];
+/// let result = butina(&fps, 0.4, soergel);
+/// assert_eq!(result.clusters[0].members, [0, 1]);
+/// assert_eq!(result.singletons, [2]);
+///
+/// let pruned = Butina {
+///     bound: Some(soergel_bound),
+///     ..Butina::new(0.4, soergel)
+/// };
+/// assert_eq!(pruned.fit(&fps), result);
+/// ```
+pub fn butina(fps: &[Vec<u8>], threshold: f64, metric: Metric) -> CentroidClustering {
+    Butina::new(threshold, metric).fit(fps)
+}
@@ -0,0 +1,93 @@
+use super::{fingerprint_len, ClusterResult, Measure, Metric};
+use ndarray::Array2;
+use petal_clustering::{Dbscan, Fit, HDbscan};
+
+/// Fingerprints as rows of 0.0/1.0 values, to be compared through `Measure`
+///
+/// Panics if the fingerprints have different lengths.
+fn bit_matrix(fps: &[Vec<u8>]) -> Array2<f64> {
+    let width = fingerprint_len(fps);
+    Array2::from_shape_fn((fps.len(), width), |(i, j)| fps[i][j] as f64)
+}
+
+/// Clusters from `petal_clustering`, numbered by their lowest-indexed member
+fn numbered<I: IntoIterator<Item = Vec<usize>>>(n: usize, clusters: I) -> ClusterResult {
+    let mut clusters = clusters
+        .into_iter()
+        .map(|mut members| {
+            members.sort_unstable();
+            members
+        })
+        .collect::<Vec<_>>();
+    clusters.sort_unstable();
+    ClusterResult::from_clusters(n, &clusters)
+}
+
+/// DBSCAN clustering of fingerprints under `metric`, e.g. `soergel` for the
+/// Jaccard/Tanimoto distance
+///
+/// Objects with at least `min_samples` objects within `eps`, counting themselves, are
+/// core objects, and every cluster is a connected group of core objects together with
+/// the objects within `eps` of them; clusters left with fewer than `min_samples` members
+/// are noise. Clusters are numbered by their lowest-indexed member.
+///
+/// Neighbours are found with a ball tree, which may or may not count an object at
+/// exactly `eps`, so `eps` is best chosen between the distances the metric can take.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::cluster::dbscan;
+/// use similarity_metrics::measures::soergel;
+/// let fps = vec![
+///     vec![1, 1, 1, 1, 0, 0, 0, 0],
+///     vec![1, 1, 1, 0, 0, 0, 0, 0],
+///     vec![1, 1, 0, 1, 0, 0, 0, 0],
+///     vec![0, 0, 0, 0, 1, 1, 1, 1],
+///     vec![0, 0, 0, 0, 1, 1, 1, 0],
+///     vec![0, 0, 0, 0, 0, 1, 1, 1],
+///     vec![1, 0, 0, 0, 0, 0, 0, 1],
+/// ];
+/// let result = dbscan(&fps, 0.3, 2, soergel);
+/// assert_eq!(result.clusters, [vec![0, 1, 2], vec![3, 4, 5]]);
+/// assert_eq!(result.noise, [6]);
+/// ```
+pub fn dbscan(fps: &[Vec<u8>], eps: f64, min_samples: usize, metric: Metric) -> ClusterResult {
+    let (clusters, _) = Dbscan::new(eps, min_samples, Measure(metric)).fit(&bit_matrix(fps), None);
+    numbered(fps.len(), clusters.into_values())
+}
+
+/// HDBSCAN clustering of fingerprints under `metric`, e.g. `soergel` for the
+/// Jaccard/Tanimoto distance
+///
+/// Core distances are taken to the `min_samples`-th neighbour, and clusters with fewer
+/// than `min_cluster_size` members are not kept. Returns the clusters, numbered by their
+/// lowest-indexed member, together with the GLOSH outlier score of every object.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::cluster::hdbscan;
+/// use similarity_metrics::measures::soergel;
+/// let mut fps = vec![vec![1, 1, 1, 1, 0, 0, 0, 0]; 5];
+/// fps.extend(vec![vec![0, 0, 0, 0, 1, 1, 1, 1]; 5]);
+/// let (result, scores) = hdbscan(&fps, 3, 3, soergel);
+/// assert_eq!(result.clusters, [(0..5).collect::<Vec<_>>(), (5..10).collect()]);
+/// assert_eq!(scores.len(), fps.len());
+/// ```
+pub fn hdbscan(
+    fps: &[Vec<u8>],
+    min_samples: usize,
+    min_cluster_size: usize,
+    metric: Metric,
+) -> (ClusterResult, Vec<f64>) {
+    let mut model = HDbscan {
+        alpha: 1.0,
+        min_samples,
+        min_cluster_size,
+        metric: Measure(metric),
+        boruvka: true,
+    };
+    let (clusters, _, scores) = model.fit(&bit_matrix(fps), None);
+    (numbered(fps.len(), clusters.into_values()), scores)
+}
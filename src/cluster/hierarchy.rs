@@ -0,0 +1,290 @@
+use super::{ClusterResult, DisjointSets};
+use ndarray::Array2;
+
+/// How the distance between two clusters is derived from distances between their members
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Linkage {
+    /// Distance between the closest members
+    Single,
+    /// Distance between the furthest members
+    Complete,
+    /// Mean distance over all pairs of members (UPGMA)
+    Average,
+    /// Increase in within-cluster variance; only meaningful for Euclidean distances
+    Ward,
+}
+
+impl Linkage {
+    /// Lance-Williams update: distance from the union of clusters `i` and `j`
+    /// (of sizes `ni` and `nj`) to cluster `k` (of size `nk`)
+    fn update(self, (ni, nj, nk): (usize, usize, usize), dij: f64, dik: f64, djk: f64) -> f64 {
+        let (ni, nj, nk) = (ni as f64, nj as f64, nk as f64);
+        match self {
+            Linkage::Single => dik.min(djk),
+            Linkage::Complete => dik.max(djk),
+            Linkage::Average => (ni * dik + nj * djk) / (ni + nj),
+            Linkage::Ward => (((ni + nk) * dik * dik + (nj + nk) * djk * djk - nk * dij * dij)
+                / (ni + nj + nk))
+                .max(0.0)
+                .sqrt(),
+        }
+    }
+}
+
+/// Merge of two clusters, numbered as in SciPy: `0..n` are the original objects
+/// and `n + i` is the cluster formed by the `i`-th merge
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Merge {
+    pub left: usize,
+    pub right: usize,
+    pub distance: f64,
+    /// Number of objects in the merged cluster
+    pub size: usize,
+}
+
+/// Hierarchy of `n - 1` merges over `n` objects, in order of increasing distance
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dendrogram {
+    pub n: usize,
+    pub merges: Vec<Merge>,
+}
+
+impl Dendrogram {
+    /// Number the merges of `pairs`, given as `(a, b, distance)` with `a` and `b` any
+    /// objects of the two clusters, after stably sorting them by distance
+    fn from_pairs(n: usize, mut pairs: Vec<(usize, usize, f64)>) -> Self {
+        pairs.sort_by(|a, b| a.2.total_cmp(&b.2));
+
+        let mut sets = DisjointSets::new(n);
+        let mut ids = (0..n).collect::<Vec<_>>();
+        let mut sizes = vec![1; n];
+        let merges = pairs
+            .into_iter()
+            .enumerate()
+            .map(|(i, (a, b, distance))| {
+                let (a, b) = (sets.find(a), sets.find(b));
+                let (left, right) = (ids[a].min(ids[b]), ids[a].max(ids[b]));
+                let size = sizes[a] + sizes[b];
+
+                let root = sets.union(a, b);
+                ids[root] = n + i;
+                sizes[root] = size;
+                Merge {
+                    left,
+                    right,
+                    distance,
+                    size,
+                }
+            })
+            .collect();
+
+        Dendrogram { n, merges }
+    }
+
+    /// SciPy-compatible linkage matrix, with one `[left, right, distance, size]` row per merge
+    pub fn linkage_matrix(&self) -> Array2<f64> {
+        Array2::from_shape_vec(
+            (self.merges.len(), 4),
+            self.merges
+                .iter()
+                .flat_map(|m| [m.left as f64, m.right as f64, m.distance, m.size as f64])
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    /// Newick tree with the given leaf names and branch lengths from the merge distances
+    ///
+    /// Names are written as given, so they must not contain Newick punctuation.
+    /// Panics unless there is exactly one name per object.
+    pub fn newick<S: AsRef<str>>(&self, names: &[S]) -> String {
+        assert!(
+            names.len() == self.n,
+            "newick needs one name per object, got {} names for {} objects",
+            names.len(),
+            self.n
+        );
+        let height = |node: usize| {
+            node.checked_sub(self.n)
+                .map_or(0.0, |i| self.merges[i].distance)
+        };
+
+        let root = match self.merges.len() {
+            0 if self.n == 0 => return ";".to_string(),
+            0 => 0,
+            m => self.n + m - 1,
+        };
+
+        // Depth-first walk that closes each merge after writing both of its children
+        let mut tree = String::new();
+        let mut stack = vec![(root, None, false)];
+        while let Some((node, parent, closing)) = stack.pop() {
+            if node >= self.n && !closing {
+                let Merge { left, right, .. } = self.merges[node - self.n];
+                tree.push('(');
+                stack.extend([
+                    (node, parent, true),
+                    (right, Some(node), false),
+                    (left, Some(node), false),
+                ]);
+                continue;
+            }
+
+            if node < self.n {
+                tree.push_str(names[node].as_ref());
+            } else {
+                tree.push(')');
+            }
+            if let Some(p) = parent {
+                tree.push_str(&format!(":{}", height(p) - height(node)));
+            }
+            if stack.last().is_some_and(|&(_, _, closing)| !closing) {
+                tree.push(',');
+            }
+        }
+
+        tree.push(';');
+        tree
+    }
+
+    fn labels_after(&self, merges: usize) -> ClusterResult {
+        // Any one object of each node, to find its set
+        let mut sets = DisjointSets::new(self.n);
+        let mut objects = (0..self.n).collect::<Vec<_>>();
+        for m in &self.merges[..merges] {
+            objects.push(sets.union(objects[m.left], objects[m.right]));
+        }
+        ClusterResult::from_labels(sets.labels())
+    }
+
+    /// Flat clustering joining every merge at or below `distance`
+    ///
+    /// Clusters are numbered by their lowest-indexed member.
+    pub fn cut_distance(&self, distance: f64) -> ClusterResult {
+        self.labels_after(self.merges.partition_point(|m| m.distance <= distance))
+    }
+
+    /// Flat clustering into `k` clusters, or as close as the number of objects allows
+    ///
+    /// Clusters are numbered by their lowest-indexed member.
+    pub fn cut_k(&self, k: usize) -> ClusterResult {
+        self.labels_after(self.n.saturating_sub(k.max(1)).min(self.merges.len()))
+    }
+}
+
+/// Single linkage from a minimum spanning tree, built with Prim's algorithm
+fn single(distances: &Array2<f64>) -> Vec<(usize, usize, f64)> {
+    let n = distances.nrows();
+    let mut in_tree = vec![false; n];
+    let mut nearest = vec![(0, f64::INFINITY); n];
+    let mut edges = Vec::with_capacity(n.saturating_sub(1));
+
+    let mut current = 0;
+    for _ in 1..n {
+        in_tree[current] = true;
+        let mut next = None;
+        for k in (0..n).filter(|&k| !in_tree[k]) {
+            if distances[[current, k]] < nearest[k].1 {
+                nearest[k] = (current, distances[[current, k]]);
+            }
+            if next.is_none_or(|j: usize| nearest[k].1 < nearest[j].1) {
+                next = Some(k);
+            }
+        }
+
+        let k = next.unwrap();
+        edges.push((nearest[k].0, k, nearest[k].1));
+        current = k;
+    }
+    edges
+}
+
+/// Nearest-neighbour chain algorithm for linkages with the reducibility property,
+/// updating the distances in place
+fn nn_chain(mut d: Array2<f64>, linkage: Linkage) -> Vec<(usize, usize, f64)> {
+    let n = d.nrows();
+    let mut sizes = vec![1; n];
+    let mut active = vec![true; n];
+    let mut chain: Vec<usize> = Vec::new();
+    let mut pairs = Vec::with_capacity(n.saturating_sub(1));
+
+    for _ in 1..n {
+        if chain.is_empty() {
+            chain.push(active.iter().position(|&a| a).unwrap());
+        }
+
+        let (a, b) = loop {
+            let a = chain[chain.len() - 1];
+            // Prefer the previous element of the chain on ties, so the chain always ends
+            let previous = chain.len().checked_sub(2).map(|i| chain[i]);
+            let mut best = previous;
+            for k in (0..n).filter(|&k| active[k] && k != a) {
+                if best.is_none_or(|b| d[[a, k]] < d[[a, b]]) {
+                    best = Some(k);
+                }
+            }
+
+            let b = best.unwrap();
+            if Some(b) == previous {
+                break (a, b);
+            }
+            chain.push(b);
+        };
+        chain.truncate(chain.len() - 2);
+
+        // The merged cluster takes the place of `b`
+        let dab = d[[a, b]];
+        pairs.push((a, b, dab));
+        active[a] = false;
+        for k in (0..n).filter(|&k| active[k] && k != b) {
+            let dk = linkage.update((sizes[a], sizes[b], sizes[k]), dab, d[[a, k]], d[[b, k]]);
+            d[[b, k]] = dk;
+            d[[k, b]] = dk;
+        }
+        sizes[b] += sizes[a];
+    }
+    pairs
+}
+
+/// Agglomerative hierarchical clustering from a symmetric matrix of distances,
+/// e.g. from `distance_matrix`
+///
+/// Single linkage is built from a minimum spanning tree and the other linkages with
+/// the nearest-neighbour chain algorithm, both in `O(n²)` time on top of the matrix.
+/// The matrix is taken by value because the chain algorithm overwrites it with the
+/// distances between merged clusters.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::cluster::{hierarchical, Linkage};
+/// let distances = ndarray::arr2(&[
+///     [0.0, 1.0, 4.0, 5.0],
+///     [1.0, 0.0, 3.0, 6.0],
+///     [4.0, 3.0, 0.0, 2.0],
+///     [5.0, 6.0, 2.0, 0.0],
+/// ]);
+/// let dendrogram = hierarchical(distances, Linkage::Complete);
+/// assert_eq!(
+///     dendrogram.linkage_matrix(),
+///     ndarray::arr2(&[
+///         [0.0, 1.0, 1.0, 2.0],
+///         [2.0, 3.0, 2.0, 2.0],
+///         [4.0, 5.0, 6.0, 4.0],
+///     ])
+/// );
+/// assert_eq!(
+///     dendrogram.newick(&["a", "b", "c", "d"]),
+///     "((a:1,b:1):5,(c:2,d:2):4);"
+/// );
+/// assert_eq!(dendrogram.cut_distance(3.0).clusters, [vec![0, 1], vec![2, 3]]);
+/// assert_eq!(dendrogram.cut_k(3).labels, [Some(0), Some(0), Some(1), Some(2)]);
+/// ```
+pub fn hierarchical(distances: Array2<f64>, linkage: Linkage) -> Dendrogram {
+    let n = distances.nrows();
+    let pairs = match linkage {
+        Linkage::Single => single(&distances),
+        _ => nn_chain(distances, linkage),
+    };
+    Dendrogram::from_pairs(n, pairs)
+}
@@ -0,0 +1,98 @@
+use super::{ClusterResult, DisjointSets, Metric};
+use rayon::prelude::*;
+
+/// The `k` nearest neighbours of every fingerprint under `metric`, excluding itself,
+/// sorted by distance and then index
+///
+/// Each list is found by a parallel brute-force scan.
+pub fn knn(fps: &[Vec<u8>], k: usize, metric: Metric) -> Vec<Vec<usize>> {
+    fps.par_iter()
+        .enumerate()
+        .map(|(i, fp)| {
+            let mut distances = fps
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .map(|(j, other)| (metric(fp, other), j))
+                .collect::<Vec<_>>();
+            let by_distance =
+                |a: &(f64, usize), b: &(f64, usize)| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1));
+
+            if distances.len() > k {
+                distances.select_nth_unstable_by(k, by_distance);
+                distances.truncate(k);
+            }
+            distances.sort_unstable_by(by_distance);
+            distances.into_iter().map(|(_, j)| j).collect()
+        })
+        .collect()
+}
+
+/// Jarvis-Patrick shared-nearest-neighbour clustering
+///
+/// Two objects are joined if each is among the first `j` entries of the other's
+/// neighbour list, and those two lists of `j` neighbours share at least `shared`
+/// objects. Clusters are the connected groups of joined objects, numbered by their
+/// lowest index; objects joined to no other are left as noise. Neighbour lists can
+/// come from `knn` with any metric and must exclude the object itself.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::cluster::{jarvis_patrick, knn};
+/// use similarity_metrics::measures::soergel;
+/// let fps = vec![
+///     vec![1, 1, 1, 1, 0, 0, 0, 0],
+///     vec![1, 1, 1, 0, 0, 0, 0, 0],
+///     vec![1, 1, 0, 1, 0, 0, 0, 0],
+///     vec![0, 0, 0, 0, 1, 1, 1, 1],
+///     vec![0, 0, 0, 0, 1, 1, 1, 0],
+///     vec![0, 0, 0, 0, 0, 1, 1, 1],
+///     vec![1, 0, 0, 0, 0, 0, 0, 1],
+/// ];
+/// let result = jarvis_patrick(&knn(&fps, 3, soergel), 2, 1);
+/// assert_eq!(result.clusters, [vec![0, 1, 2], vec![3, 4, 5]]);
+/// assert_eq!(result.noise, [6]);
+/// ```
+pub fn jarvis_patrick(neighbours: &[Vec<usize>], j: usize, shared: usize) -> ClusterResult {
+    let lists = neighbours
+        .iter()
+        .map(|list| {
+            let mut list = list[..j.min(list.len())].to_vec();
+            list.sort_unstable();
+            list
+        })
+        .collect::<Vec<_>>();
+    let count_shared =
+        |a: &[usize], b: &[usize]| a.iter().filter(|x| b.binary_search(x).is_ok()).count();
+
+    let mut sets = DisjointSets::new(lists.len());
+    let mut joined = vec![false; lists.len()];
+    for (a, list) in lists.iter().enumerate() {
+        for &b in list.iter().filter(|&&b| b > a) {
+            if lists[b].binary_search(&a).is_ok() && count_shared(list, &lists[b]) >= shared {
+                sets.union(a, b);
+                joined[a] = true;
+                joined[b] = true;
+            }
+        }
+    }
+
+    // Number the clusters again without the objects left on their own
+    let labels = sets.labels();
+    let mut numbers = vec![None; lists.len()];
+    let mut count = 0;
+    let labels = labels
+        .iter()
+        .zip(&joined)
+        .map(|(label, &joined)| {
+            joined.then(|| {
+                *numbers[label.unwrap()].get_or_insert_with(|| {
+                    count += 1;
+                    count - 1
+                })
+            })
+        })
+        .collect();
+    ClusterResult::from_labels(labels)
+}
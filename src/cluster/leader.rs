@@ -0,0 +1,187 @@
+use super::{ClusterResult, Metric};
+use crate::measures::soergel;
+use rayon::prelude::*;
+use std::io::{self, BufRead, Write};
+
+/// Leader (sphere-exclusion) clustering that takes fingerprints one at a time
+///
+/// Each fingerprint joins the nearest leader within `radius`, the earliest on ties,
+/// or becomes a new leader if there is none. New fingerprints can be added at any time,
+/// and the leaders can be saved and loaded to continue clustering later.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::cluster::Leaders;
+/// let mut leaders = Leaders::tanimoto(0.4);
+/// let labels = leaders.add(&[vec![1, 1, 1, 0, 0, 0], vec![0, 0, 0, 1, 1, 1]]);
+/// assert_eq!(labels, [0, 1]);
+/// let labels = leaders.add(&[vec![1, 1, 0, 0, 0, 0], vec![1, 0, 0, 0, 0, 1]]);
+/// assert_eq!(labels, [0, 2]);
+/// assert_eq!(leaders.sizes(), [2, 1, 1]);
+/// assert_eq!(leaders.origins(), [0, 1, 3]);
+///
+/// let mut saved = Vec::new();
+/// leaders.save(&mut saved).unwrap();
+/// let reloaded = Leaders::load(&saved[..], leaders.metric).unwrap();
+/// assert_eq!(reloaded.leaders(), leaders.leaders());
+/// assert_eq!(reloaded.sizes(), leaders.sizes());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Leaders {
+    /// Largest distance from a fingerprint to its leader
+    pub radius: f64,
+    pub metric: Metric,
+    leaders: Vec<Vec<u8>>,
+    origins: Vec<usize>,
+    sizes: Vec<usize>,
+    seen: usize,
+}
+
+impl Leaders {
+    pub fn new(radius: f64, metric: Metric) -> Self {
+        Leaders {
+            radius,
+            metric,
+            leaders: Vec::new(),
+            origins: Vec::new(),
+            sizes: Vec::new(),
+            seen: 0,
+        }
+    }
+
+    /// Leaders on Soergel distance, i.e. members have a Tanimoto similarity of at least
+    /// `1 - radius` to their leader
+    pub fn tanimoto(radius: f64) -> Self {
+        Leaders::new(radius, soergel)
+    }
+
+    pub fn len(&self) -> usize {
+        self.leaders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaders.is_empty()
+    }
+
+    /// Fingerprint of every leader
+    pub fn leaders(&self) -> &[Vec<u8>] {
+        &self.leaders
+    }
+
+    /// Position of every leader in the stream of all fingerprints added so far
+    pub fn origins(&self) -> &[usize] {
+        &self.origins
+    }
+
+    /// Number of fingerprints assigned to every leader, including itself
+    pub fn sizes(&self) -> &[usize] {
+        &self.sizes
+    }
+
+    /// Number of fingerprints added so far
+    pub fn seen(&self) -> usize {
+        self.seen
+    }
+
+    /// Nearest leader within `radius` of `fp`, the earliest on ties
+    pub fn nearest(&self, fp: &[u8]) -> Option<usize> {
+        self.leaders
+            .par_iter()
+            .enumerate()
+            .map(|(i, leader)| (i, (self.metric)(fp, leader)))
+            .filter(|&(_, d)| d <= self.radius)
+            .min_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)))
+            .map(|(i, _)| i)
+    }
+
+    /// Assign every fingerprint in turn, returning the leader of each
+    pub fn add(&mut self, fps: &[Vec<u8>]) -> Vec<usize> {
+        fps.iter()
+            .map(|fp| {
+                let leader = self.nearest(fp).unwrap_or_else(|| {
+                    self.leaders.push(fp.clone());
+                    self.origins.push(self.seen);
+                    self.sizes.push(0);
+                    self.leaders.len() - 1
+                });
+                self.sizes[leader] += 1;
+                self.seen += 1;
+                leader
+            })
+            .collect()
+    }
+
+    /// Write the radius and leaders as tab-separated text: a `radius` and a `seen` line,
+    /// then the origin, size and bits (as `0`s and `1`s) of one leader per line
+    pub fn save<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "radius\t{}", self.radius)?;
+        writeln!(writer, "seen\t{}", self.seen)?;
+        for ((leader, origin), size) in self.leaders.iter().zip(&self.origins).zip(&self.sizes) {
+            let bits = leader
+                .iter()
+                .map(|&bit| if bit == 0 { '0' } else { '1' })
+                .collect::<String>();
+            writeln!(writer, "{origin}\t{size}\t{bits}")?;
+        }
+        Ok(())
+    }
+
+    /// Read leaders written by `save`, to be compared with `metric`
+    pub fn load<R: BufRead>(reader: R, metric: Metric) -> io::Result<Self> {
+        fn invalid(line: &str) -> io::Error {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid leader line: {line}"),
+            )
+        }
+        fn field<T: std::str::FromStr>(line: &str, field: Option<&str>) -> io::Result<T> {
+            field
+                .and_then(|f| f.parse().ok())
+                .ok_or_else(|| invalid(line))
+        }
+
+        let mut lines = reader.lines();
+        let mut header = |name: &str| -> io::Result<String> {
+            let line = lines.next().transpose()?.unwrap_or_default();
+            match line.split_once('\t') {
+                Some((key, value)) if key == name => Ok(value.to_string()),
+                _ => Err(invalid(&line)),
+            }
+        };
+        let radius = header("radius")?;
+        let seen = header("seen")?;
+        let mut leaders = Leaders {
+            seen: field(&seen, Some(&seen))?,
+            ..Leaders::new(field(&radius, Some(&radius))?, metric)
+        };
+
+        for line in lines {
+            let line = line?;
+            let mut fields = line.split('\t');
+            leaders.origins.push(field(&line, fields.next())?);
+            leaders.sizes.push(field(&line, fields.next())?);
+            leaders.leaders.push(
+                fields
+                    .next()
+                    .unwrap_or_default()
+                    .chars()
+                    .map(|c| match c {
+                        '0' => Ok(0),
+                        '1' => Ok(1),
+                        _ => Err(invalid(&line)),
+                    })
+                    .collect::<io::Result<_>>()?,
+            );
+        }
+        Ok(leaders)
+    }
+}
+
+/// Leader clustering of `fps` in one pass, with leaders `radius` apart under `metric`
+///
+/// Every cluster is numbered after its leader, in order of appearance.
+pub fn leader(fps: &[Vec<u8>], radius: f64, metric: Metric) -> ClusterResult {
+    let labels = Leaders::new(radius, metric).add(fps);
+    ClusterResult::from_labels(labels.into_iter().map(Some).collect())
+}
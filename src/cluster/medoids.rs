@@ -0,0 +1,294 @@
+use super::{distance_matrix, seeded_rng, ClusterResult, Metric, DEFAULT_METRIC, DEFAULT_SEED};
+use ndarray::Array2;
+use rand::seq::index::sample;
+use rayon::prelude::*;
+
+/// k-medoids clustering, in which every cluster is centred on one of its members
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Medoids {
+    /// Index of each cluster's medoid
+    pub medoids: Vec<usize>,
+    /// Cluster of every object, i.e. the position of its nearest medoid in `medoids`
+    pub labels: Vec<usize>,
+    /// Sum of distances from every object to its medoid
+    pub cost: f64,
+}
+
+impl Medoids {
+    /// Assign every one of `n` objects to its nearest medoid, the first one on ties
+    fn assign<F: Fn(usize, usize) -> f64 + Sync>(
+        n: usize,
+        medoids: Vec<usize>,
+        distance: F,
+    ) -> Self {
+        let nearest = (0..n)
+            .into_par_iter()
+            .map(|o| {
+                medoids.iter().map(|&m| distance(o, m)).enumerate().fold(
+                    (0, f64::INFINITY),
+                    |best, (i, d)| if d < best.1 { (i, d) } else { best },
+                )
+            })
+            .collect::<Vec<_>>();
+
+        Medoids {
+            medoids,
+            labels: nearest.iter().map(|&(i, _)| i).collect(),
+            cost: nearest.iter().map(|&(_, d)| d).sum(),
+        }
+    }
+
+    pub fn result(&self) -> ClusterResult {
+        ClusterResult::from_labels(self.labels.iter().copied().map(Some).collect())
+    }
+}
+
+/// Nearest and second-nearest medoid of an object, as positions in the medoid list,
+/// with their distances
+#[derive(Debug, Clone, Copy)]
+struct Nearest {
+    near: usize,
+    dn: f64,
+    second: usize,
+    ds: f64,
+}
+
+fn nearest(distances: &Array2<f64>, medoids: &[usize], o: usize) -> Nearest {
+    let mut best = Nearest {
+        near: 0,
+        dn: f64::INFINITY,
+        second: 0,
+        ds: f64::INFINITY,
+    };
+    for (i, &m) in medoids.iter().enumerate() {
+        let d = distances[[o, m]];
+        if d < best.dn {
+            best = Nearest {
+                near: i,
+                dn: d,
+                second: best.near,
+                ds: best.dn,
+            };
+        } else if d < best.ds {
+            best.second = i;
+            best.ds = d;
+        }
+    }
+    best
+}
+
+/// Update an object's nearest medoids after the medoid at position `i` was replaced
+/// by one at distance `d`, scanning all medoids only if the old one was among the two
+fn update_nearest(
+    distances: &Array2<f64>,
+    medoids: &[usize],
+    o: usize,
+    near: Nearest,
+    i: usize,
+    d: f64,
+) -> Nearest {
+    if near.near == i || near.second == i {
+        nearest(distances, medoids, o)
+    } else if d < near.dn {
+        Nearest {
+            near: i,
+            dn: d,
+            second: near.near,
+            ds: near.dn,
+        }
+    } else if d < near.ds {
+        Nearest {
+            second: i,
+            ds: d,
+            ..near
+        }
+    } else {
+        near
+    }
+}
+
+/// Increase in cost from removing each medoid, with every object falling back
+/// to its second-nearest medoid
+fn removal_loss(nearest: &[Nearest], k: usize) -> Vec<f64> {
+    nearest.iter().fold(vec![0.0; k], |mut loss, o| {
+        loss[o.near] += o.ds - o.dn;
+        loss
+    })
+}
+
+/// FasterPAM k-medoids (Schubert and Rousseeuw) on a symmetric matrix of distances,
+/// e.g. from `distance_matrix`
+///
+/// Starts from `k` medoids drawn with `seed` and eagerly applies the first swap of a
+/// medoid with a non-medoid that lowers the total cost, until no swap does.
+/// Returns no medoids and no labels if `k` is 0 or larger than the number of objects.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::cluster::fasterpam;
+/// let distances = ndarray::arr2(&[
+///     [0.0, 1.0, 2.0, 9.0, 9.0],
+///     [1.0, 0.0, 1.0, 9.0, 9.0],
+///     [2.0, 1.0, 0.0, 9.0, 9.0],
+///     [9.0, 9.0, 9.0, 0.0, 1.0],
+///     [9.0, 9.0, 9.0, 1.0, 0.0],
+/// ]);
+/// let mut result = fasterpam(&distances, 2, 0);
+/// assert_eq!(result.cost, 3.0);
+/// result.medoids.sort();
+/// assert!(result.medoids == [1, 3] || result.medoids == [1, 4]);
+/// assert!(fasterpam(&distances, 6, 0).medoids.is_empty());
+/// ```
+pub fn fasterpam(distances: &Array2<f64>, k: usize, seed: u64) -> Medoids {
+    let n = distances.nrows();
+    if k == 0 || k > n {
+        return Medoids::default();
+    }
+    let mut medoids = sample(&mut seeded_rng(seed), n, k).into_vec();
+
+    let mut nearest_of = (0..n)
+        .map(|o| nearest(distances, &medoids, o))
+        .collect::<Vec<_>>();
+    let mut loss = removal_loss(&nearest_of, k);
+
+    // Cycle through candidates until a whole pass finds no improving swap
+    let mut last_swap = 0;
+    let mut candidate = 0;
+    loop {
+        if !medoids.contains(&candidate) {
+            let mut delta = loss.clone();
+            let mut gain = 0.0;
+            for (o, near) in nearest_of.iter().enumerate() {
+                let d = distances[[o, candidate]];
+                if d < near.dn {
+                    gain += d - near.dn;
+                    delta[near.near] += near.dn - near.ds;
+                } else if d < near.ds {
+                    delta[near.near] += d - near.ds;
+                }
+            }
+
+            let i = delta
+                .iter()
+                .enumerate()
+                .min_by(|a, b| a.1.total_cmp(b.1))
+                .unwrap()
+                .0;
+            if delta[i] + gain < -1e-12 {
+                medoids[i] = candidate;
+                for (o, near) in nearest_of.iter_mut().enumerate() {
+                    *near =
+                        update_nearest(distances, &medoids, o, *near, i, distances[[o, candidate]]);
+                }
+                loss = removal_loss(&nearest_of, k);
+                last_swap = candidate;
+            }
+        }
+
+        candidate = (candidate + 1) % n;
+        if candidate == last_swap {
+            break;
+        }
+    }
+
+    Medoids::assign(n, medoids, |o, m| distances[[o, m]])
+}
+
+/// k-medoids clustering of fingerprints
+///
+/// Sets of at most `max_matrix` fingerprints are clustered with `fasterpam` on the full
+/// distance matrix. Larger sets use CLARA: FasterPAM runs on `samples` random subsets
+/// of `sample_size` fingerprints, each including the best medoids found so far, and
+/// the medoids with the lowest cost over the whole set are kept.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::cluster::KMedoids;
+/// let fps = vec![
+///     vec![1, 1, 1, 0, 0, 0],
+///     vec![1, 1, 0, 0, 0, 0],
+///     vec![1, 1, 1, 1, 0, 0],
+///     vec![0, 0, 0, 1, 1, 1],
+///     vec![0, 0, 0, 0, 1, 1],
+/// ];
+/// let result = KMedoids::new(2).fit(&fps);
+/// assert_eq!(result.labels[0], result.labels[1]);
+/// assert_eq!(result.labels[3], result.labels[4]);
+/// assert_ne!(result.labels[0], result.labels[3]);
+/// assert!(result.medoids.contains(&0));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct KMedoids {
+    /// Number of clusters
+    pub k: usize,
+    pub metric: Metric,
+    pub seed: u64,
+    /// Largest number of fingerprints clustered on the full distance matrix
+    pub max_matrix: usize,
+    /// Number of subsets tried by CLARA
+    pub samples: usize,
+    /// Size of each CLARA subset
+    pub sample_size: usize,
+}
+
+impl KMedoids {
+    /// Configuration using `DEFAULT_METRIC`, `DEFAULT_SEED`, the full matrix for up to
+    /// 5000 fingerprints, and 5 CLARA subsets of `40 + 2k` fingerprints otherwise
+    pub fn new(k: usize) -> Self {
+        KMedoids {
+            k,
+            metric: DEFAULT_METRIC,
+            seed: DEFAULT_SEED,
+            max_matrix: 5000,
+            samples: 5,
+            sample_size: 40 + 2 * k,
+        }
+    }
+
+    /// Returns no medoids and no labels if `k` is 0 or larger than the number of
+    /// fingerprints, as `fasterpam` does
+    pub fn fit(&self, fps: &[Vec<u8>]) -> Medoids {
+        if self.k == 0 || self.k > fps.len() {
+            Medoids::default()
+        } else if fps.len() <= self.max_matrix {
+            fasterpam(&distance_matrix(fps, self.metric), self.k, self.seed)
+        } else {
+            self.clara(fps)
+        }
+    }
+
+    fn clara(&self, fps: &[Vec<u8>]) -> Medoids {
+        let mut rng = seeded_rng(self.seed);
+        let sample_size = self.sample_size.max(self.k).min(fps.len());
+        let mut best: Option<Medoids> = None;
+
+        for _ in 0..self.samples.max(1) {
+            let mut subset = best.as_ref().map_or(vec![], |b| b.medoids.clone());
+            for i in sample(&mut rng, fps.len(), sample_size) {
+                if subset.len() < sample_size && !subset.contains(&i) {
+                    subset.push(i);
+                }
+            }
+
+            let fit = fasterpam(
+                &distance_matrix(
+                    &subset.iter().map(|&i| fps[i].clone()).collect::<Vec<_>>(),
+                    self.metric,
+                ),
+                self.k,
+                self.seed,
+            );
+            let medoids = fit.medoids.iter().map(|&m| subset[m]).collect();
+            let candidate =
+                Medoids::assign(fps.len(), medoids, |o, m| (self.metric)(&fps[o], &fps[m]));
+
+            if best.as_ref().is_none_or(|b| candidate.cost < b.cost) {
+                best = Some(candidate);
+            }
+        }
+
+        best.unwrap()
+    }
+}
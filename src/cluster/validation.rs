@@ -0,0 +1,358 @@
+use super::{argmin, seeded_rng, Metric};
+use rand::seq::index::sample;
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+
+/// Indices of the labelled objects, checking that every fingerprint has a label
+fn labelled(fps: &[Vec<u8>], labels: &[Option<usize>]) -> Vec<usize> {
+    assert_eq!(
+        labels.len(),
+        fps.len(),
+        "expected one label per fingerprint"
+    );
+    (0..fps.len()).filter(|&i| labels[i].is_some()).collect()
+}
+
+/// Members of every cluster in order of label, and the position of every object's
+/// cluster in that list, leaving out noise
+fn groups(labels: &[Option<usize>]) -> (Vec<Vec<usize>>, Vec<Option<usize>>) {
+    let mut by_label = BTreeMap::new();
+    for (i, label) in labels.iter().enumerate() {
+        if let Some(c) = label {
+            by_label.entry(*c).or_insert_with(Vec::new).push(i);
+        }
+    }
+
+    let clusters = by_label.into_values().collect::<Vec<_>>();
+    let mut cluster_of = vec![None; labels.len()];
+    for (c, members) in clusters.iter().enumerate() {
+        for &i in members {
+            cluster_of[i] = Some(c);
+        }
+    }
+    (clusters, cluster_of)
+}
+
+/// Member with the lowest sum of distances to the others, the first on ties
+fn medoid(fps: &[Vec<u8>], members: &[usize], metric: Metric) -> usize {
+    let sums = members
+        .par_iter()
+        .map(|&i| members.iter().map(|&j| metric(&fps[i], &fps[j])).sum())
+        .collect::<Vec<f64>>();
+    members[argmin(&sums)]
+}
+
+/// Mean silhouette of `objects`, each compared with every other labelled object
+fn mean_silhouette(
+    fps: &[Vec<u8>],
+    labels: &[Option<usize>],
+    objects: &[usize],
+    metric: Metric,
+) -> f64 {
+    let (clusters, cluster_of) = groups(labels);
+    if clusters.len() < 2 {
+        return f64::NAN;
+    }
+
+    let total = objects
+        .par_iter()
+        .map(|&i| {
+            let own = cluster_of[i].unwrap();
+            if clusters[own].len() == 1 {
+                return 0.0;
+            }
+            let mean = |members: &[usize]| {
+                let others = members.iter().filter(|&&j| j != i);
+                others.map(|&j| metric(&fps[i], &fps[j])).sum::<f64>()
+                    / (members.len() - (members.contains(&i) as usize)) as f64
+            };
+
+            let a = mean(&clusters[own]);
+            let b = clusters
+                .iter()
+                .enumerate()
+                .filter(|&(c, _)| c != own)
+                .map(|(_, members)| mean(members))
+                .fold(f64::INFINITY, f64::min);
+            match a.max(b) {
+                0.0 => 0.0,
+                furthest => (b - a) / furthest,
+            }
+        })
+        .sum::<f64>();
+    total / objects.len() as f64
+}
+
+/// Mean silhouette coefficient of a clustering of `fps` under `metric`
+///
+/// Each object compares its mean distance `a` to the rest of its cluster with its mean
+/// distance `b` to the nearest other cluster, as `(b - a) / max(a, b)`; objects alone
+/// in their cluster score 0. Noise is left out, and with fewer than two clusters the
+/// result is NaN. Panics unless there is one label per fingerprint.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::cluster::silhouette;
+/// use similarity_metrics::measures::euclidean;
+/// let fps = vec![vec![0, 0], vec![0, 1], vec![1, 1], vec![1, 1]];
+/// let labels = [Some(0), Some(0), Some(1), Some(1)];
+/// let expected = (1.0 - 1.0 / 2f64.sqrt() + 0.0 + 1.0 + 1.0) / 4.0;
+/// assert!((silhouette(&fps, &labels, euclidean) - expected).abs() < 1e-12);
+/// ```
+pub fn silhouette(fps: &[Vec<u8>], labels: &[Option<usize>], metric: Metric) -> f64 {
+    mean_silhouette(fps, labels, &labelled(fps, labels), metric)
+}
+
+/// Mean silhouette coefficient over `sample_size` labelled objects drawn with `seed`,
+/// each still compared with every labelled object; see `silhouette`
+///
+/// There is nothing to average over with a `sample_size` of 0, so the result is NaN.
+pub fn silhouette_sampled(
+    fps: &[Vec<u8>],
+    labels: &[Option<usize>],
+    sample_size: usize,
+    seed: u64,
+    metric: Metric,
+) -> f64 {
+    let labelled = labelled(fps, labels);
+    if sample_size == 0 {
+        return f64::NAN;
+    }
+    let objects = sample(
+        &mut seeded_rng(seed),
+        labelled.len(),
+        sample_size.min(labelled.len()),
+    )
+    .into_iter()
+    .map(|i| labelled[i])
+    .collect::<Vec<_>>();
+    mean_silhouette(fps, labels, &objects, metric)
+}
+
+/// Davies-Bouldin index of a clustering of `fps` under `metric`, lower being better
+///
+/// Clusters are centred on their medoids, so any metric can be used: the scatter of a
+/// cluster is the mean distance of its members to the medoid, and each cluster is
+/// scored by its worst ratio of summed scatter to medoid distance against any other.
+/// Clusters sharing a medoid fingerprint are not compared. Noise is left out, and
+/// with fewer than two clusters the result is NaN. Panics unless there is one label
+/// per fingerprint.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::cluster::davies_bouldin;
+/// use similarity_metrics::measures::euclidean;
+/// let fps = vec![vec![0, 0], vec![0, 1], vec![1, 1], vec![1, 1]];
+/// let labels = [Some(0), Some(0), Some(1), Some(1)];
+/// assert_eq!(davies_bouldin(&fps, &labels, euclidean), 0.5 / 2f64.sqrt());
+/// ```
+pub fn davies_bouldin(fps: &[Vec<u8>], labels: &[Option<usize>], metric: Metric) -> f64 {
+    labelled(fps, labels);
+    let (clusters, _) = groups(labels);
+    if clusters.len() < 2 {
+        return f64::NAN;
+    }
+
+    let medoids = clusters
+        .iter()
+        .map(|members| medoid(fps, members, metric))
+        .collect::<Vec<_>>();
+    let scatter = clusters
+        .iter()
+        .zip(&medoids)
+        .map(|(members, &m)| {
+            members
+                .iter()
+                .map(|&i| metric(&fps[i], &fps[m]))
+                .sum::<f64>()
+                / members.len() as f64
+        })
+        .collect::<Vec<_>>();
+
+    let k = clusters.len();
+    let worst = (0..k).map(|a| {
+        (0..k)
+            .filter(|&b| b != a)
+            .map(|b| match metric(&fps[medoids[a]], &fps[medoids[b]]) {
+                0.0 => 0.0,
+                separation => (scatter[a] + scatter[b]) / separation,
+            })
+            .fold(0.0, f64::max)
+    });
+    worst.sum::<f64>() / k as f64
+}
+
+/// Calinski-Harabasz index of a clustering of `fps` under `metric`, higher being better
+///
+/// The ratio of between-cluster to within-cluster dispersion, each divided by its
+/// degrees of freedom, with clusters and the whole set centred on their medoids so any
+/// metric can be used. Finding the overall medoid takes `O(n²)` distances. Noise is
+/// left out; with fewer than two clusters the result is NaN, and without any
+/// within-cluster dispersion it is 1. Panics unless there is one label per fingerprint.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::cluster::calinski_harabasz;
+/// use similarity_metrics::measures::euclidean;
+/// let fps = vec![vec![0, 0], vec![0, 1], vec![1, 1], vec![1, 1]];
+/// let labels = [Some(0), Some(0), Some(1), Some(1)];
+/// // Only the first cluster's medoid [0, 0] lies off the overall medoid [1, 1],
+/// // and only [0, 1] lies off its cluster's medoid
+/// let expected = (4.0 / 1.0) / (1.0 / 2.0);
+/// assert!((calinski_harabasz(&fps, &labels, euclidean) - expected).abs() < 1e-12);
+/// ```
+pub fn calinski_harabasz(fps: &[Vec<u8>], labels: &[Option<usize>], metric: Metric) -> f64 {
+    labelled(fps, labels);
+    let (clusters, _) = groups(labels);
+    if clusters.len() < 2 {
+        return f64::NAN;
+    }
+
+    let labelled = clusters.concat();
+    let centre = medoid(fps, &labelled, metric);
+    let (mut between, mut within) = (0.0, 0.0);
+    for members in &clusters {
+        let m = medoid(fps, members, metric);
+        between += members.len() as f64 * metric(&fps[m], &fps[centre]).powi(2);
+        within += members
+            .iter()
+            .map(|&i| metric(&fps[i], &fps[m]).powi(2))
+            .sum::<f64>();
+    }
+
+    let (n, k) = (labelled.len() as f64, clusters.len() as f64);
+    if within == 0.0 {
+        1.0
+    } else {
+        between * (n - k) / (within * (k - 1.0))
+    }
+}
+
+/// Cross-tabulation of two clusterings, with noise counted as one more cluster
+struct Contingency {
+    /// Number of objects in every pair of clusters sharing any, as positions in the
+    /// sizes of each clustering
+    joint: Vec<(usize, usize, usize)>,
+    sizes_a: Vec<usize>,
+    sizes_b: Vec<usize>,
+}
+
+impl Contingency {
+    fn new(a: &[Option<usize>], b: &[Option<usize>]) -> Self {
+        assert_eq!(a.len(), b.len(), "clusterings must label the same objects");
+        let mut pairs = BTreeMap::new();
+        for (x, y) in a.iter().zip(b) {
+            *pairs.entry((x, y)).or_insert(0) += 1;
+        }
+        let sizes = |labels: &[Option<usize>]| {
+            let mut sizes = BTreeMap::new();
+            for label in labels {
+                *sizes.entry(*label).or_insert(0) += 1;
+            }
+            sizes
+        };
+        let (sizes_a, sizes_b) = (sizes(a), sizes(b));
+        let position = |sizes: &BTreeMap<Option<usize>, usize>, label| sizes.range(..label).count();
+
+        Contingency {
+            joint: pairs
+                .into_iter()
+                .map(|((&x, &y), count)| (position(&sizes_a, x), position(&sizes_b, y), count))
+                .collect(),
+            sizes_a: sizes_a.into_values().collect(),
+            sizes_b: sizes_b.into_values().collect(),
+        }
+    }
+}
+
+/// Adjusted Rand index between two clusterings of the same objects
+///
+/// 1 for identical clusterings up to numbering and about 0 for random ones. Noise is
+/// counted as one more cluster, like the label -1 in scikit-learn.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::cluster::adjusted_rand_index;
+/// let a = [Some(0), Some(0), Some(1), Some(1)];
+/// let b = [Some(1), Some(1), Some(0), None];
+/// assert_eq!(adjusted_rand_index(&a, &a), 1.0);
+/// assert!((adjusted_rand_index(&a, &b) - 4.0 / 7.0).abs() < 1e-12);
+/// ```
+pub fn adjusted_rand_index(a: &[Option<usize>], b: &[Option<usize>]) -> f64 {
+    let pairs = |n: usize| (n * n.saturating_sub(1) / 2) as f64;
+    let Contingency {
+        joint,
+        sizes_a,
+        sizes_b,
+    } = Contingency::new(a, b);
+    if a.len() < 2 {
+        return 1.0;
+    }
+
+    let index = joint
+        .into_iter()
+        .map(|(_, _, count)| pairs(count))
+        .sum::<f64>();
+    let pairs_a = sizes_a.into_iter().map(pairs).sum::<f64>();
+    let pairs_b = sizes_b.into_iter().map(pairs).sum::<f64>();
+    let expected = pairs_a * pairs_b / pairs(a.len());
+    let max = (pairs_a + pairs_b) / 2.0;
+    if max == expected {
+        1.0
+    } else {
+        (index - expected) / (max - expected)
+    }
+}
+
+/// Normalized mutual information between two clusterings of the same objects
+///
+/// Mutual information divided by the arithmetic mean of the two entropies, as by
+/// default in scikit-learn: 1 for identical clusterings up to numbering and 0 for
+/// independent ones. Noise is counted as one more cluster.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::cluster::normalized_mutual_info;
+/// let a = [Some(0), Some(0), Some(1), Some(1)];
+/// let b = [Some(1), Some(1), Some(0), None];
+/// assert_eq!(normalized_mutual_info(&a, &a), 1.0);
+/// assert!((normalized_mutual_info(&a, &b) - 0.8).abs() < 1e-12);
+/// ```
+pub fn normalized_mutual_info(a: &[Option<usize>], b: &[Option<usize>]) -> f64 {
+    let n = a.len() as f64;
+    let entropy = |sizes: &[usize]| {
+        sizes
+            .iter()
+            .map(|&s| s as f64 / n)
+            .map(|p| -p * p.ln())
+            .sum::<f64>()
+    };
+
+    let Contingency {
+        joint,
+        sizes_a,
+        sizes_b,
+    } = Contingency::new(a, b);
+    if sizes_a.len() <= 1 && sizes_b.len() <= 1 {
+        return 1.0;
+    }
+
+    let mutual = joint
+        .into_iter()
+        .map(|(x, y, count)| {
+            let p = count as f64 / n;
+            p * (p * n * n / (sizes_a[x] * sizes_b[y]) as f64).ln()
+        })
+        .sum::<f64>();
+    let mean_entropy = (entropy(&sizes_a) + entropy(&sizes_b)) / 2.0;
+    if mutual == 0.0 {
+        0.0
+    } else {
+        (mutual / mean_entropy).clamp(0.0, 1.0)
+    }
+}
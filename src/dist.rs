@@ -1,18 +1,23 @@
+use crate::{qgram, tokenize};
+use ndarray::Array2;
+use rayon::prelude::*;
+use std::fmt::Display;
+use std::hash::Hash;
+
+/// Lookup table keyed by sequence symbols; it is never iterated, so the hash order
+/// cannot leak into results
+#[allow(clippy::disallowed_types)]
+type SymbolMap<K, V> = std::collections::HashMap<K, V>;
+
 pub type Pointer = Option<isize>;
 pub type Locations = Vec<isize>;
 
-#[derive(Debug)]
-struct Ordered {
-    long: String,
-    short: String,
-}
-
 /// Return longer length of two strings
 ///
 /// ## Examples
 ///
 /// ```
-/// use similarity_metrics::dist::longer;
+/// use similarity_metrics::dist::LONGER as longer;
 /// assert_eq!(longer("hello", "hello world"), 11);
 /// assert_eq!(longer("hello", "hello"), 5);
 /// assert_eq!(longer("CAPS", "lower"), 5);
@@ -25,27 +30,12 @@ struct Ordered {
 pub const LONGER: fn(&str, &str) -> isize =
     |a: &str, b: &str| std::cmp::max(a.len() as isize, b.len() as isize);
 
-/// Return ordered pair of strings
-const ORDER: fn(&str, &str) -> Ordered = |a: &str, b: &str| {
-    if a.len() > b.len() {
-        Ordered {
-            long: a.to_string(),
-            short: b.to_string(),
-        }
-    } else {
-        Ordered {
-            long: b.to_string(),
-            short: a.to_string(),
-        }
-    }
-};
-
 /// Check whether `a` is the `i`th element of string `b`
 ///
 /// ## Examples
 ///
 /// ```
-/// use similarity_metrics::dist::is;
+/// use similarity_metrics::dist::IS as is;
 /// assert_eq!(is('h', "hello", 0), true);
 /// assert_eq!(is('e', "hello", 0), false);
 /// assert_eq!(is('e', "hello", 2), false);
@@ -73,7 +63,7 @@ pub const IS: fn(char, &str, isize) -> bool = |a: char, b: &str, i: isize| {
 /// ## Examples
 ///
 /// ```
-/// use similarity_metrics::dist::matches;
+/// use similarity_metrics::dist::MATCHES as matches;
 /// assert_eq!(matches('h', "hello", 0, 2), Some(0));
 /// assert_eq!(matches('o', "hello", 0, 2), None);
 /// assert_eq!(matches('e', "hello", 2, 1), Some(1));
@@ -89,7 +79,7 @@ pub const MATCHES: fn(char, &str, isize, isize) -> Pointer =
 /// ## Examples
 ///
 /// ```
-/// use similarity_metrics::dist::last;
+/// use similarity_metrics::dist::LAST as last;
 /// assert_eq!(last("hello", 5), "hello");
 /// assert_eq!(last("hello", 3), "llo");
 /// assert_eq!(last("hello", 0), "");
@@ -104,7 +94,7 @@ pub const LAST: fn(&str, usize) -> &str = |a: &str, i: usize| &a[(a.len() - i)..
 ///
 /// ```
 /// use similarity_metrics::dist::Locations;
-/// use similarity_metrics::dist::unique_matches;
+/// use similarity_metrics::dist::UNIQUE_MATCHES as unique_matches;
 /// let acc: Locations = vec![0, 2];
 /// assert_eq!(unique_matches('h', "hello", 0, 2, &acc, 0), None);
 /// assert_eq!(unique_matches('o', "hello", 0, 2, &acc, 0), None);
@@ -135,7 +125,7 @@ pub const UNIQUE_MATCHES: fn(char, &str, isize, isize, &Locations, isize) -> Poi
 /// ## Examples
 ///
 /// ```
-/// use similarity_metrics::dist::radius;
+/// use similarity_metrics::dist::RADIUS as radius;
 /// assert_eq!(radius("hello", "hello world"), 4);
 /// assert_eq!(radius("FAREMVIEL", "FARMVILLE"), 3);
 /// assert_eq!(radius("winkler", "welfare"), 2);
@@ -157,7 +147,7 @@ pub const RADIUS: fn(&str, &str) -> isize =
 ///
 /// ```
 /// use similarity_metrics::dist::Locations;
-/// use similarity_metrics::dist::append;
+/// use similarity_metrics::dist::APPEND as append;
 ///
 /// let empty: Locations = vec![];
 /// assert_eq!(append(empty, 0), [0]);
@@ -182,7 +172,7 @@ pub const APPEND: fn(Locations, isize) -> Locations =
 ///
 /// ```
 /// use similarity_metrics::dist::Locations;
-/// use similarity_metrics::dist::matched;
+/// use similarity_metrics::dist::MATCHED as matched;
 ///
 /// let acc: Locations = vec![0, 2];
 /// assert_eq!(matched(&acc), 1);
@@ -207,7 +197,7 @@ pub const MATCHED: fn(&Locations) -> isize = |permutation: &Locations| {
 ///
 /// ```
 /// use similarity_metrics::dist::Locations;
-/// use similarity_metrics::dist::unmatched;
+/// use similarity_metrics::dist::UNMATCHED as unmatched;
 ///
 /// let acc: Locations = vec![0, 2];
 /// assert_eq!(unmatched(&acc), 1);
@@ -228,7 +218,7 @@ pub const UNMATCHED: fn(&Locations) -> isize =
 ///
 /// ```
 /// use similarity_metrics::dist::Locations;
-/// use similarity_metrics::dist::shifts;
+/// use similarity_metrics::dist::SHIFTS as shifts;
 ///
 /// let acc: Locations = vec![0, 2];
 /// let shifted: Vec<Locations> = vec![vec![0, 2], vec![0, 0, 2]];
@@ -254,7 +244,7 @@ pub const SHIFTS: fn(Locations) -> Vec<Locations> = |permutation: Locations| {
     (0..permutation.len() as isize)
         .map(|i| {
             [
-                std::iter::repeat(0).take(i as usize).collect(),
+                std::iter::repeat_n(0, i as usize).collect(),
                 permutation.clone(),
             ]
             .concat()
@@ -269,7 +259,7 @@ pub const SHIFTS: fn(Locations) -> Vec<Locations> = |permutation: Locations| {
 ///
 /// ```
 /// use similarity_metrics::dist::Locations;
-/// use similarity_metrics::dist::revShifts;
+/// use similarity_metrics::dist::REV_SHIFTS as revShifts;
 ///
 /// let acc: Locations = vec![0, 2];
 /// let shifted: Vec<Locations> = vec![vec![2, 0]];
@@ -299,7 +289,7 @@ pub const REV_SHIFTS: fn(Locations) -> Vec<Locations> = |permutation: Locations|
                     .skip(i as usize)
                     .cloned()
                     .collect::<Locations>(),
-                std::iter::repeat(0).take(i as usize).collect(),
+                std::iter::repeat_n(0, i as usize).collect(),
             ]
             .concat()
         })
@@ -311,7 +301,7 @@ pub const REV_SHIFTS: fn(Locations) -> Vec<Locations> = |permutation: Locations|
 /// ## Examples
 ///
 /// ```
-/// use similarity_metrics::dist::roundHalf;
+/// use similarity_metrics::dist::ROUND_HALF as roundHalf;
 ///
 /// assert_eq!(roundHalf(0), 0);
 /// assert_eq!(roundHalf(1), 1);
@@ -335,7 +325,7 @@ pub const ROUND_HALF: fn(isize) -> isize = |x: isize| (x / 2) + (x % 2);
 ///
 /// ```
 /// use similarity_metrics::dist::Locations;
-/// use similarity_metrics::dist::transpositions;
+/// use similarity_metrics::dist::TRANSPOSITIONS as transpositions;
 ///
 /// let acc: Locations = vec![0, 2, 1, 3];
 /// assert_eq!(transpositions(acc), 1);
@@ -364,7 +354,7 @@ pub const TRANSPOSITIONS: fn(Locations) -> isize = |permutation: Locations| {
 ///
 /// ```
 /// use similarity_metrics::dist::Locations;
-/// use similarity_metrics::dist::sequence;
+/// use similarity_metrics::dist::SEQUENCE as sequence;
 ///
 /// let acc: Locations = vec![0, 2, 1, 5];
 /// let sequenced: Locations = vec![0, 2, 1, 3];
@@ -381,23 +371,6 @@ pub const SEQUENCE: fn(Locations) -> Locations = |permutation: Locations| {
         .collect()
 };
 
-const MATCHING_ORDERED: fn(Ordered, isize) -> Locations = |pair: Ordered, r: isize| {
-    (0..pair.short.len()).fold(Vec::new(), |acc, i| {
-        if let Some(j) = UNIQUE_MATCHES(
-            pair.short.chars().nth(i).unwrap(),
-            pair.long.as_str(),
-            i as isize,
-            r,
-            &acc,
-            0,
-        ) {
-            APPEND(acc, j)
-        } else {
-            acc
-        }
-    })
-};
-
 const GEN_JARO_METRICS: fn(Locations) -> (isize, isize) = |permutation: Locations| {
     (
         permutation.len() as isize,
@@ -411,10 +384,12 @@ const GEN_JARO_METRICS: fn(Locations) -> (isize, isize) = |permutation: Location
 /// Transpositions are calculated according to a custom algorithm
 /// (see `similarity_metrics::dist::transpositions` for more details)
 ///
+/// Strings are compared by `char`, so multi-byte characters count once.
+///
 /// ## Examples
 ///
 /// ```
-/// use similarity_metrics::dist::matching;
+/// use similarity_metrics::dist::MATCHING as matching;
 /// assert_eq!(matching("hello", "hello world"), (5, 0));
 /// assert_eq!(matching("---amyez---", "---zayem---"), (11, 2));
 /// assert_eq!(matching("---zayem---", "---amyez---"), (11, 2));
@@ -434,31 +409,7 @@ const GEN_JARO_METRICS: fn(Locations) -> (isize, isize) = |permutation: Location
 /// assert_eq!(matching("a", "ab"), (1, 0));
 /// ```
 pub const MATCHING: fn(&str, &str) -> (isize, isize) =
-    |a: &str, b: &str| GEN_JARO_METRICS(MATCHING_ORDERED(ORDER(a, b), RADIUS(a, b)));
-
-/// Jaro similarity, in [0, 1]
-///
-/// Given a number of matching characters `n_matching` and
-/// a number of transpositions `n_transpositions`,
-/// calculate the Jaro distance between two strings `a` and `b`.
-/// Helper function for `similarity_metrics::dist::jaro`.
-const NONZERO_JARO_SIM: fn(&str, &str, isize, isize) -> f64 =
-    |a: &str, b: &str, n_matching: isize, n_transpositions: isize| {
-        1.0 / 3.0
-            * (n_matching as f64 / a.len() as f64
-                + n_matching as f64 / b.len() as f64
-                + (n_matching - n_transpositions) as f64 / n_matching as f64)
-    };
-
-/// Choose correct Jaro helper function
-const JARO_SIM: fn(&str, &str, (isize, isize)) -> f64 =
-    |a: &str, b: &str, metrics: (isize, isize)| {
-        if metrics.0 == 0 {
-            0.0
-        } else {
-            NONZERO_JARO_SIM(a, b, metrics.0, metrics.1)
-        }
-    };
+    |a: &str, b: &str| _matching(&tokenize::chars(a), &tokenize::chars(b));
 
 /// Jaro distance, in [0, 1]
 ///
@@ -470,7 +421,7 @@ const JARO_SIM: fn(&str, &str, (isize, isize)) -> f64 =
 /// ## Examples
 ///
 /// ```
-/// use similarity_metrics::dist::jaro;
+/// use similarity_metrics::dist::JARO as jaro;
 /// const epsilon: f64 = 1e-6;
 /// const around: fn(f64, f64) -> bool = |a: f64, b: f64| (a - b).abs() <= epsilon;
 /// const frac: fn(isize, isize) -> f64 = |a: isize, b: isize| (a as f64) / (b as f64);
@@ -493,14 +444,15 @@ const JARO_SIM: fn(&str, &str, (isize, isize)) -> f64 =
 /// assert!(around(jaro("a", ""), zero));
 /// assert!(around(jaro("a", "ab"), frac(5, 6)));
 /// ```
-pub const JARO: fn(&str, &str) -> f64 = |a: &str, b: &str| JARO_SIM(a, b, MATCHING(a, b));
+pub const JARO: fn(&str, &str) -> f64 =
+    |a: &str, b: &str| _jaro(&tokenize::chars(a), &tokenize::chars(b));
 
 /// Length of longest common prefix between two strings
 ///
 /// ## Examples
 ///
 /// ```
-/// use similarity_metrics::dist::len_common_prefix;
+/// use similarity_metrics::dist::LEN_COMMON_PREFIX as len_common_prefix;
 /// assert_eq!(len_common_prefix("hello", "hello world"), 5);
 /// assert_eq!(len_common_prefix("hello", "world"), 0);
 /// assert_eq!(len_common_prefix("hello", "hell"), 4);
@@ -535,7 +487,7 @@ const JARO_WINKLER_SIM: fn(f64, f64, isize) -> f64 =
 /// ## Examples
 ///
 /// ```
-/// use similarity_metrics::dist::jaro_winkler;
+/// use similarity_metrics::dist::JARO_WINKLER as jaro_winkler;
 /// const epsilon: f64 = 1e-6;
 /// const around: fn(f64, f64) -> bool = |a: f64, b: f64| (a - b).abs() <= epsilon;
 /// const frac: fn(isize, isize) -> f64 = |a: isize, b: isize| (a as f64) / (b as f64);
@@ -599,13 +551,8 @@ const JARO_WINKLER_SIM: fn(f64, f64, isize) -> f64 =
 ///     jaro_winkler("ab", "a", Some(zero)), frac(5, 6)
 /// ))
 /// ```
-pub const JARO_WINKLER: fn(&str, &str, Option<f64>) -> f64 = |a: &str, b: &str, p: Option<f64>| {
-    JARO_WINKLER_SIM(
-        JARO(a, b),
-        f64::min(p.unwrap_or(0.1), 0.25),
-        std::cmp::min(LEN_COMMON_PREFIX(a, b), 4),
-    )
-};
+pub const JARO_WINKLER: fn(&str, &str, Option<f64>) -> f64 =
+    |a: &str, b: &str, p: Option<f64>| _jaro_winkler(&tokenize::chars(a), &tokenize::chars(b), p);
 
 /// Jaro–Winkler distance with custom prefix length, in [0, 1]
 ///
@@ -642,7 +589,7 @@ const JARO_WINKLER_EXT_SIM: fn(f64, f64, isize, isize) -> f64 =
 /// ## Examples
 ///
 /// ```
-/// use similarity_metrics::dist::jaro_winkler_ext;
+/// use similarity_metrics::dist::JARO_WINKLER_EXT as jaro_winkler_ext;
 /// const epsilon: f64 = 1e-6;
 /// const around: fn(f64, f64) -> bool = |a: f64, b: f64| (a - b).abs() <= epsilon;
 /// const frac: fn(isize, isize) -> f64 = |a: isize, b: isize| (a as f64) / (b as f64);
@@ -669,17 +616,12 @@ const JARO_WINKLER_EXT_SIM: fn(f64, f64, isize, isize) -> f64 =
 /// ```
 pub const JARO_WINKLER_EXT: fn(&str, &str, Option<f64>, Option<isize>) -> f64 =
     |a: &str, b: &str, p: Option<f64>, l: Option<isize>| {
-        JARO_WINKLER_EXT_SIM(
-            JARO(a, b),
-            p.unwrap_or(0.1),
-            LEN_COMMON_PREFIX(a, b),
-            l.unwrap_or(std::cmp::min(a.len() as isize, b.len() as isize)),
-        )
+        _jaro_winkler_ext(&tokenize::chars(a), &tokenize::chars(b), p, l)
     };
 
-fn lcs_helper(
-    x: &[char],
-    y: &[char],
+fn lcs_helper<T: PartialEq>(
+    x: &[T],
+    y: &[T],
     i: usize,
     j: usize,
     memo: &mut Vec<Vec<Option<usize>>>,
@@ -706,7 +648,8 @@ fn lcs_helper(
     }
 }
 
-fn _lcs(x: &[char], y: &[char]) -> usize {
+/// Length of the longest common subsequence of two sequences
+pub fn _lcs<T: PartialEq>(x: &[T], y: &[T]) -> usize {
     let m = x.len();
     let n = y.len();
 
@@ -722,7 +665,19 @@ pub fn lcs(x: &str, y: &str) -> usize {
     _lcs(&_x, &_y)
 }
 
-pub fn _l_distance(s: &[char], t: &[char]) -> usize {
+/// Levenshtein distance between two sequences
+///
+/// Generic counterpart of `l_distance`, for any sequence of comparable symbols.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::_l_distance;
+/// let residues = |s: &str| s.chars().collect::<Vec<char>>();
+/// assert_eq!(_l_distance(&residues("MKIV"), &residues("MLV")), 2);
+/// assert_eq!(_l_distance(&[1, 2, 3, 4], &[1, 3, 4, 5]), 2);
+/// ```
+pub fn _l_distance<T: PartialEq>(s: &[T], t: &[T]) -> usize {
     let (m, n) = (s.len(), t.len());
 
     // Ensure the shorter string is 's' for memory efficiency
@@ -754,3 +709,1347 @@ pub fn l_distance(s: &str, t: &str) -> usize {
 
     _l_distance(&_s, &_t)
 }
+
+/// Length of the longest common subsequence of two token sequences
+///
+/// Works like `lcs`, but compares whole tokens instead of characters, so a
+/// multi-character atom such as `Cl` or `[C@@H]` is matched as a single unit.
+/// See `similarity_metrics::tokenize` for SMILES and SELFIES tokenizers.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::lcs_tokens;
+/// use similarity_metrics::tokenize::{selfies, smiles};
+/// assert_eq!(lcs_tokens(&smiles("CCCl"), &smiles("CCBr")), 2);
+/// assert_eq!(lcs_tokens(&smiles("C[C@@H](O)N"), &smiles("C[C@H](O)N")), 5);
+/// assert_eq!(lcs_tokens(&selfies("[C][Branch1][C][O]"), &selfies("[C][O]")), 2);
+/// assert_eq!(lcs_tokens(&[], &smiles("CO")), 0);
+/// ```
+pub fn lcs_tokens(x: &[&str], y: &[&str]) -> usize {
+    _lcs(x, y)
+}
+
+/// Levenshtein distance between two token sequences
+///
+/// Works like `l_distance`, but each insertion, deletion or substitution acts on
+/// a whole token, so swapping `Cl` for `Br` or `[C@@H]` for `[C@H]` costs one edit.
+/// See `similarity_metrics::tokenize` for SMILES and SELFIES tokenizers.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::{l_distance, l_distance_tokens};
+/// use similarity_metrics::tokenize::{selfies, smiles};
+/// assert_eq!(l_distance("CCCl", "CCBr"), 2);
+/// assert_eq!(l_distance_tokens(&smiles("CCCl"), &smiles("CCBr")), 1);
+/// assert_eq!(l_distance("C[C@@H](O)N", "C[C@H](O)N"), 1);
+/// assert_eq!(l_distance_tokens(&smiles("C[C@@H](O)N"), &smiles("C[C@H](O)N")), 1);
+/// assert_eq!(l_distance_tokens(&smiles("CC(=O)O"), &smiles("CC(=O)OC")), 1);
+/// assert_eq!(l_distance_tokens(&selfies("[C][Branch1][C][O][C]"), &selfies("[C][C]")), 3);
+/// assert_eq!(l_distance_tokens(&[], &smiles("CCl")), 2);
+/// ```
+pub fn l_distance_tokens(s: &[&str], t: &[&str]) -> usize {
+    _l_distance(s, t)
+}
+
+/// Levenshtein distance between `s` and `t` if it is at most `k`, otherwise `None`
+///
+/// Ukkonen's banded algorithm: only cells within `k` of the main diagonal are computed,
+/// and the computation stops as soon as every cell in a row exceeds `k`.
+pub fn _l_distance_banded<T: PartialEq>(s: &[T], t: &[T], k: usize) -> Option<usize> {
+    let (m, n) = (s.len(), t.len());
+    if m.abs_diff(n) > k {
+        return None;
+    }
+
+    let over = k + 1;
+    let mut prev_row = (0..=n).map(|j| j.min(over)).collect::<Vec<usize>>();
+    let mut curr_row = vec![over; n + 1];
+
+    for i in 1..=m {
+        let (lo, hi) = (i.saturating_sub(k), usize::min(n, i + k));
+        if lo == 0 {
+            curr_row[0] = i;
+        } else {
+            curr_row[lo - 1] = over;
+        }
+        if hi < n {
+            curr_row[hi + 1] = over;
+        }
+
+        for j in usize::max(lo, 1)..=hi {
+            curr_row[j] = if s[i - 1] == t[j - 1] {
+                prev_row[j - 1]
+            } else {
+                1 + prev_row[j].min(curr_row[j - 1]).min(prev_row[j - 1])
+            }
+            .min(over);
+        }
+
+        if curr_row[lo..=hi].iter().all(|&d| d > k) {
+            return None;
+        }
+        std::mem::swap(&mut prev_row, &mut curr_row);
+    }
+
+    Some(prev_row[n]).filter(|&d| d <= k)
+}
+
+/// Advance one 64-row block of Myers' bit vectors by one column of the text
+///
+/// `pv`/`mv` hold the positive/negative vertical deltas of the block,
+/// `eq` the rows whose pattern symbol equals the text symbol, and `h_in` the
+/// horizontal delta entering the top of the block. Returns the horizontal delta
+/// leaving the row selected by `high`.
+fn myers_block(pv: &mut u64, mv: &mut u64, eq: u64, h_in: i8, high: u64) -> i8 {
+    let xv = eq | *mv;
+    let eq = eq | u64::from(h_in < 0);
+    let xh = ((eq & *pv).wrapping_add(*pv) ^ *pv) | eq;
+    let mut ph = *mv | !(xh | *pv);
+    let mut mh = *pv & xh;
+
+    let h_out = if ph & high != 0 {
+        1
+    } else if mh & high != 0 {
+        -1
+    } else {
+        0
+    };
+
+    ph <<= 1;
+    mh <<= 1;
+    if h_in < 0 {
+        mh |= 1;
+    } else if h_in > 0 {
+        ph |= 1;
+    }
+    *pv = mh | !(xv | ph);
+    *mv = ph & xv;
+
+    h_out
+}
+
+/// Levenshtein distance using Myers' bit-parallel algorithm
+///
+/// The shorter sequence is packed into 64-bit words, so sequences of up to 64 symbols
+/// are compared with a handful of word operations per symbol of the other sequence;
+/// longer ones are split into blocks of 64 (Hyyrö's extension).
+pub fn _l_distance_myers<T: Eq + Hash>(s: &[T], t: &[T]) -> usize {
+    let (pattern, text) = if s.len() <= t.len() { (s, t) } else { (t, s) };
+    let m = pattern.len();
+    if m == 0 {
+        return text.len();
+    }
+
+    let blocks = m.div_ceil(64);
+    let peq = pattern.iter().enumerate().fold(
+        SymbolMap::new(),
+        |mut acc: SymbolMap<&T, Vec<u64>>, (i, c)| {
+            acc.entry(c).or_insert_with(|| vec![0; blocks])[i / 64] |= 1 << (i % 64);
+            acc
+        },
+    );
+    let no_match = vec![0; blocks];
+    let last_high = 1 << ((m - 1) % 64);
+
+    let mut pv = vec![!0u64; blocks];
+    let mut mv = vec![0u64; blocks];
+    let mut score = m as isize;
+
+    for c in text {
+        let eq = peq.get(c).unwrap_or(&no_match);
+        let h_out = (0..blocks).fold(1, |h_in, b| {
+            let high = if b + 1 == blocks { last_high } else { 1 << 63 };
+            myers_block(&mut pv[b], &mut mv[b], eq[b], h_in, high)
+        });
+        score += h_out as isize;
+    }
+
+    score as usize
+}
+
+/// Levenshtein distance between two strings using Myers' bit-parallel algorithm
+///
+/// Returns the same value as `l_distance`, but is much faster for short strings.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::{l_distance, l_distance_myers};
+/// assert_eq!(l_distance_myers("kitten", "sitting"), 3);
+/// assert_eq!(l_distance_myers("CC(=O)OC1=CC=CC=C1C(=O)O", "CC(=O)NC1=CC=C(O)C=C1"), 9);
+/// assert_eq!(l_distance_myers("", "CCO"), 3);
+/// assert_eq!(l_distance_myers("CCO", ""), 3);
+///
+/// let long = "C".repeat(70) + "O" + &"N".repeat(70);
+/// let other = "C".repeat(69) + "S" + &"N".repeat(75);
+/// assert_eq!(l_distance_myers(&long, &other), l_distance(&long, &other));
+/// ```
+pub fn l_distance_myers(s: &str, t: &str) -> usize {
+    let _s = s.chars().collect::<Vec<char>>();
+    let _t = t.chars().collect::<Vec<char>>();
+
+    _l_distance_myers(&_s, &_t)
+}
+
+/// Levenshtein distance between `s` and `t` if it is at most `k`, otherwise `None`
+///
+/// Picks between Myers' bit-parallel algorithm and Ukkonen's banded algorithm,
+/// depending on which does less work: the band of `2k + 1` cells per row, or one
+/// 64-bit word per 64 symbols of the shorter sequence.
+pub fn _l_distance_bounded<T: Eq + Hash>(s: &[T], t: &[T], k: usize) -> Option<usize> {
+    if s.len().abs_diff(t.len()) > k {
+        None
+    } else if usize::min(s.len(), t.len()).div_ceil(64) * 64 <= 2 * k + 1 {
+        Some(_l_distance_myers(s, t)).filter(|&d| d <= k)
+    } else {
+        _l_distance_banded(s, t, k)
+    }
+}
+
+/// Levenshtein distance between two strings if it is at most `k`, otherwise `None`
+///
+/// Cheaper than `l_distance` when only distances up to a threshold matter,
+/// since the computation gives up as soon as `k` is exceeded.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::l_distance_bounded;
+/// assert_eq!(l_distance_bounded("kitten", "sitting", 3), Some(3));
+/// assert_eq!(l_distance_bounded("kitten", "sitting", 2), None);
+/// assert_eq!(l_distance_bounded("CCO", "CCO", 0), Some(0));
+/// assert_eq!(l_distance_bounded("CCO", "CCCCCO", 2), None);
+/// assert_eq!(l_distance_bounded("", "CCO", 40), Some(3));
+///
+/// let long = "C".repeat(100) + "O";
+/// let other = "C".repeat(99) + "N";
+/// assert_eq!(l_distance_bounded(&long, &other, 1), None);
+/// assert_eq!(l_distance_bounded(&long, &other, 2), Some(2));
+/// assert_eq!(l_distance_bounded(&long, &other, 200), Some(2));
+/// ```
+pub fn l_distance_bounded(s: &str, t: &str, k: usize) -> Option<usize> {
+    let _s = s.chars().collect::<Vec<char>>();
+    let _t = t.chars().collect::<Vec<char>>();
+
+    _l_distance_bounded(&_s, &_t, k)
+}
+
+/// Levenshtein distance between two token sequences if it is at most `k`, otherwise `None`
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::l_distance_tokens_bounded;
+/// use similarity_metrics::tokenize::smiles;
+/// assert_eq!(l_distance_tokens_bounded(&smiles("CCCl"), &smiles("CCBr"), 1), Some(1));
+/// assert_eq!(l_distance_tokens_bounded(&smiles("CCCl"), &smiles("OCBr"), 1), None);
+/// ```
+pub fn l_distance_tokens_bounded(s: &[&str], t: &[&str], k: usize) -> Option<usize> {
+    _l_distance_bounded(s, t, k)
+}
+
+/// All strings in `library` within Levenshtein distance `k` of `query`
+///
+/// Returns `(index, distance)` pairs in library order, using the bounded
+/// Levenshtein distance so that far-away entries are rejected early.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::within_l_distance;
+/// let library = ["CCO", "CCN", "CCCO", "c1ccccc1", "OCC"];
+/// assert_eq!(within_l_distance("CCO", &library, 1), [(0, 0), (1, 1), (2, 1)]);
+/// assert_eq!(within_l_distance("CCO", &library, 0), [(0, 0)]);
+/// ```
+pub fn within_l_distance<S: AsRef<str>>(
+    query: &str,
+    library: &[S],
+    k: usize,
+) -> Vec<(usize, usize)> {
+    let _query = query.chars().collect::<Vec<char>>();
+
+    library
+        .iter()
+        .enumerate()
+        .filter_map(|(i, target)| {
+            let _target = target.as_ref().chars().collect::<Vec<char>>();
+            _l_distance_bounded(&_query, &_target, k).map(|d| (i, d))
+        })
+        .collect()
+}
+
+/// Kind of a single Levenshtein edit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditOp {
+    Insert,
+    Delete,
+    Substitute,
+}
+
+/// A single Levenshtein edit turning `s` into `t`
+///
+/// `source` is the position in `s` that is deleted or substituted, or before which
+/// an insertion happens (`s.len()` for insertions at the end).
+/// `target` is the position in `t` of the inserted or substituted symbol,
+/// or where the deleted symbol would have been.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edit {
+    pub op: EditOp,
+    pub source: usize,
+    pub target: usize,
+}
+
+/// Minimal list of edits turning `s` into `t`, ordered by position
+///
+/// The number of edits equals `_l_distance(s, t)`. Among equally short scripts,
+/// substitutions are preferred over deletions, and deletions over insertions.
+pub fn _l_edits<T: PartialEq>(s: &[T], t: &[T]) -> Vec<Edit> {
+    let (m, n) = (s.len(), t.len());
+
+    let mut table = vec![vec![0; n + 1]; m + 1];
+    for (i, row) in table.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    table[0] = (0..=n).collect();
+    for i in 1..=m {
+        for j in 1..=n {
+            table[i][j] = if s[i - 1] == t[j - 1] {
+                table[i - 1][j - 1]
+            } else {
+                1 + table[i - 1][j]
+                    .min(table[i][j - 1])
+                    .min(table[i - 1][j - 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (m, n);
+    let mut edits = Vec::with_capacity(table[m][n]);
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 && s[i - 1] == t[j - 1] && table[i][j] == table[i - 1][j - 1] {
+            i -= 1;
+            j -= 1;
+            continue;
+        }
+
+        let op = if i > 0 && j > 0 && table[i][j] == table[i - 1][j - 1] + 1 {
+            EditOp::Substitute
+        } else if i > 0 && table[i][j] == table[i - 1][j] + 1 {
+            EditOp::Delete
+        } else {
+            EditOp::Insert
+        };
+        if op != EditOp::Insert {
+            i -= 1;
+        }
+        if op != EditOp::Delete {
+            j -= 1;
+        }
+        edits.push(Edit {
+            op,
+            source: i,
+            target: j,
+        });
+    }
+    edits.reverse();
+
+    edits
+}
+
+/// Apply some or all of the edits from `_l_edits(s, t)` to `s`
+///
+/// Applying every edit yields `t`; applying a subset yields an intermediate sequence
+/// between `s` and `t`.
+pub fn _apply_edits<T: Clone>(s: &[T], t: &[T], edits: &[Edit]) -> Vec<T> {
+    (0..=s.len())
+        .flat_map(|i| {
+            let inserted = edits
+                .iter()
+                .filter(move |e| e.op == EditOp::Insert && e.source == i)
+                .map(|e| t[e.target].clone());
+            let current = s.get(i).and_then(|x| {
+                match edits
+                    .iter()
+                    .find(|e| e.op != EditOp::Insert && e.source == i)
+                {
+                    Some(e) if e.op == EditOp::Delete => None,
+                    Some(e) => Some(t[e.target].clone()),
+                    None => Some(x.clone()),
+                }
+            });
+            inserted.chain(current)
+        })
+        .collect()
+}
+
+/// Minimal list of character edits turning `s` into `t`
+///
+/// Positions are character (not byte) offsets into `s` and `t`.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::{l_distance, l_edits, Edit, EditOp};
+/// assert_eq!(
+///     l_edits("kitten", "sitting"),
+///     [
+///         Edit { op: EditOp::Substitute, source: 0, target: 0 },
+///         Edit { op: EditOp::Substitute, source: 4, target: 4 },
+///         Edit { op: EditOp::Insert, source: 6, target: 6 },
+///     ]
+/// );
+/// assert_eq!(
+///     l_edits("CCCO", "CCO"),
+///     [Edit { op: EditOp::Delete, source: 0, target: 0 }]
+/// );
+/// assert_eq!(l_edits("CC(=O)O", "CC(=O)OC").len(), l_distance("CC(=O)O", "CC(=O)OC"));
+/// assert!(l_edits("CCO", "CCO").is_empty());
+/// ```
+pub fn l_edits(s: &str, t: &str) -> Vec<Edit> {
+    let _s = s.chars().collect::<Vec<char>>();
+    let _t = t.chars().collect::<Vec<char>>();
+
+    _l_edits(&_s, &_t)
+}
+
+/// Minimal list of token edits turning `s` into `t`
+///
+/// Positions are token offsets, so an edit touching `Cl` or `[C@@H]` replaces the
+/// whole token.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::{l_edits_tokens, Edit, EditOp};
+/// use similarity_metrics::tokenize::smiles;
+/// let (s, t) = (smiles("C[C@@H](Cl)O"), smiles("C[C@H](Br)O"));
+/// let edits = l_edits_tokens(&s, &t);
+/// assert_eq!(
+///     edits,
+///     [
+///         Edit { op: EditOp::Substitute, source: 1, target: 1 },
+///         Edit { op: EditOp::Substitute, source: 3, target: 3 },
+///     ]
+/// );
+/// assert_eq!((t[edits[0].target], t[edits[1].target]), ("[C@H]", "Br"));
+/// ```
+pub fn l_edits_tokens(s: &[&str], t: &[&str]) -> Vec<Edit> {
+    _l_edits(s, t)
+}
+
+/// Apply some or all of the edits from `l_edits(s, t)` to `s`
+///
+/// Applying every edit yields `t`; applying a subset yields a string part-way
+/// between `s` and `t`, e.g. to enumerate analogues along an edit path.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::{apply_edits, l_edits};
+/// let edits = l_edits("kitten", "sitting");
+/// assert_eq!(apply_edits("kitten", "sitting", &edits), "sitting");
+/// assert_eq!(apply_edits("kitten", "sitting", &edits[..1]), "sitten");
+/// assert_eq!(apply_edits("kitten", "sitting", &edits[2..]), "kitteng");
+/// assert_eq!(apply_edits("kitten", "sitting", &[]), "kitten");
+///
+/// let edits = l_edits("CCCO", "OC");
+/// assert_eq!(apply_edits("CCCO", "OC", &edits), "OC");
+/// ```
+pub fn apply_edits(s: &str, t: &str, edits: &[Edit]) -> String {
+    let _s = s.chars().collect::<Vec<char>>();
+    let _t = t.chars().collect::<Vec<char>>();
+
+    _apply_edits(&_s, &_t, edits).into_iter().collect()
+}
+
+/// Number of symbols considered "matching" by Jaro
+/// and the number of transpositions required to match those symbols
+///
+/// Generic counterpart of `similarity_metrics::dist::matching`, using the same matching
+/// radius and the same custom transposition estimate
+/// (see `similarity_metrics::dist::transpositions` for more details).
+pub fn _matching<T: PartialEq>(a: &[T], b: &[T]) -> (isize, isize) {
+    let (long, short) = if a.len() > b.len() { (a, b) } else { (b, a) };
+    let r = std::cmp::max((long.len() as isize / 2) - 1, 0);
+
+    GEN_JARO_METRICS(
+        short
+            .iter()
+            .enumerate()
+            .fold(Vec::new(), |acc, (i, symbol)| {
+                let i = i as isize;
+                match (std::cmp::max(i - r, 0)..=std::cmp::min(i + r, long.len() as isize - 1))
+                    .find(|&j| long[j as usize] == *symbol && !acc.contains(&j))
+                {
+                    Some(j) => APPEND(acc, j),
+                    None => acc,
+                }
+            }),
+    )
+}
+
+/// Jaro similarity between two sequences, in [0, 1]
+///
+/// Generic counterpart of `similarity_metrics::dist::jaro`, for any sequence of comparable
+/// symbols (tokens, residues, reaction steps, ...).
+/// 1 indicates that `a` and `b` are exactly the same.
+/// 0 indicates that there is no similarity between `a` and `b`.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::{_jaro, JARO as jaro};
+/// const epsilon: f64 = 1e-6;
+/// const around: fn(f64, f64) -> bool = |a: f64, b: f64| (a - b).abs() <= epsilon;
+/// let chars: fn(&str) -> Vec<char> = |s| s.chars().collect();
+/// assert!(around(_jaro(&chars("martha"), &chars("marhta")), jaro("martha", "marhta")));
+/// assert!(around(_jaro(&chars("DIXON"), &chars("DIRKSONX")), jaro("DIXON", "DIRKSONX")));
+///
+/// #[derive(PartialEq)]
+/// enum Step { Protect, Couple, Deprotect, Cleave }
+/// use Step::*;
+/// let route1 = [Protect, Couple, Deprotect, Couple, Cleave];
+/// let route2 = [Protect, Couple, Deprotect, Cleave];
+/// assert!(around(_jaro(&route1, &route2), (4.0 / 5.0 + 1.0 + 1.0) / 3.0));
+/// assert!(around(_jaro::<Step>(&[], &[]), 0.0));
+/// ```
+pub fn _jaro<T: PartialEq>(a: &[T], b: &[T]) -> f64 {
+    match _matching(a, b) {
+        (0, _) => 0.0,
+        (n_matching, n_transpositions) => {
+            1.0 / 3.0
+                * (n_matching as f64 / a.len() as f64
+                    + n_matching as f64 / b.len() as f64
+                    + (n_matching - n_transpositions) as f64 / n_matching as f64)
+        }
+    }
+}
+
+/// Length of longest common prefix between two sequences
+pub fn _len_common_prefix<T: PartialEq>(a: &[T], b: &[T]) -> isize {
+    a.iter().zip(b.iter()).take_while(|(x, y)| x == y).count() as isize
+}
+
+/// Jaro–Winkler similarity between two sequences, in [0, 1]
+///
+/// Generic counterpart of `similarity_metrics::dist::jaro_winkler`:
+/// `p` is the weight of the prefix, defaults to 0.1 and is capped at 0.25,
+/// and at most 4 symbols of common prefix are counted.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::{_jaro_winkler, JARO_WINKLER as jaro_winkler};
+/// use similarity_metrics::tokenize::smiles;
+/// let chars: fn(&str) -> Vec<char> = |s| s.chars().collect();
+/// assert_eq!(
+///     _jaro_winkler(&chars("DWAYNE"), &chars("DUANE"), None),
+///     jaro_winkler("DWAYNE", "DUANE", None)
+/// );
+/// assert!((_jaro_winkler(&smiles("CCCl"), &smiles("CCBr"), None) - 0.8222222).abs() < 1e-6);
+/// ```
+pub fn _jaro_winkler<T: PartialEq>(a: &[T], b: &[T], p: Option<f64>) -> f64 {
+    JARO_WINKLER_SIM(
+        _jaro(a, b),
+        f64::min(p.unwrap_or(0.1), 0.25),
+        std::cmp::min(_len_common_prefix(a, b), 4),
+    )
+}
+
+/// Jaro–Winkler similarity between two sequences with custom prefix length, in [0, 1]
+///
+/// Generic counterpart of `similarity_metrics::dist::jaro_winkler_ext`.
+/// `l` defaults to the length of the shorter sequence.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::{_jaro_winkler_ext, JARO_WINKLER_EXT as jaro_winkler_ext};
+/// let chars: fn(&str) -> Vec<char> = |s| s.chars().collect();
+/// assert_eq!(
+///     _jaro_winkler_ext(&chars("hello"), &chars("hello world"), None, None),
+///     jaro_winkler_ext("hello", "hello world", None, None)
+/// );
+/// ```
+pub fn _jaro_winkler_ext<T: PartialEq>(a: &[T], b: &[T], p: Option<f64>, l: Option<isize>) -> f64 {
+    JARO_WINKLER_EXT_SIM(
+        _jaro(a, b),
+        p.unwrap_or(0.1),
+        _len_common_prefix(a, b),
+        l.unwrap_or(std::cmp::min(a.len(), b.len()) as isize),
+    )
+}
+
+/// Number of tokens considered "matching" by Jaro
+/// and the number of transpositions required to match those tokens
+///
+/// Token counterpart of `similarity_metrics::dist::matching`; see `_matching`.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::{matching_tokens, MATCHING as matching};
+/// use similarity_metrics::tokenize::{chars, smiles};
+/// assert_eq!(matching_tokens(&chars("martha"), &chars("marhta")), matching("martha", "marhta"));
+/// assert_eq!(matching_tokens(&chars("DIXON"), &chars("DIRKSONX")), matching("DIXON", "DIRKSONX"));
+/// assert_eq!(
+///     matching_tokens(&chars("!@#ABCDE$%^"), &chars("$%^EABCD!@#")),
+///     matching("!@#ABCDE$%^", "$%^EABCD!@#")
+/// );
+/// assert_eq!(matching_tokens(&smiles("OCCCl"), &smiles("OCCBr")), (3, 0));
+/// assert_eq!(matching_tokens(&[], &[]), (0, 0));
+/// ```
+pub fn matching_tokens(a: &[&str], b: &[&str]) -> (isize, isize) {
+    _matching(a, b)
+}
+
+/// Jaro similarity between two token sequences, in [0, 1]
+///
+/// Token counterpart of `similarity_metrics::dist::jaro`; see `_jaro`.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::{jaro_tokens, JARO as jaro};
+/// use similarity_metrics::tokenize::{chars, selfies, smiles};
+/// const epsilon: f64 = 1e-6;
+/// const around: fn(f64, f64) -> bool = |a: f64, b: f64| (a - b).abs() <= epsilon;
+/// const frac: fn(isize, isize) -> f64 = |a: isize, b: isize| (a as f64) / (b as f64);
+/// assert!(around(jaro_tokens(&chars("FAREMVIEL"), &chars("FARMVILLE")), jaro("FAREMVIEL", "FARMVILLE")));
+/// assert!(around(jaro_tokens(&chars("winkler"), &chars("welfare")), jaro("winkler", "welfare")));
+/// assert!(around(jaro_tokens(&smiles("CCCl"), &smiles("CCCl")), 1.0));
+/// assert!(around(jaro_tokens(&smiles("CCCl"), &smiles("CCBr")), frac(7, 9)));
+/// assert!(around(jaro_tokens(&selfies("[C][=O]"), &selfies("[N][#N]")), 0.0));
+/// assert!(around(jaro_tokens(&[], &[]), 0.0));
+/// ```
+pub fn jaro_tokens(a: &[&str], b: &[&str]) -> f64 {
+    _jaro(a, b)
+}
+
+/// Substitution and affine gap scores for aligning sequences of `T`
+///
+/// A gap of length `k` scores `gap_open + (k - 1) * gap_extend`.
+/// Pairs without an entry in the scoring matrix score `matched` if the symbols are equal
+/// and `mismatched` otherwise.
+#[derive(Debug, Clone)]
+pub struct Scoring<T> {
+    pub matched: i32,
+    pub mismatched: i32,
+    pub gap_open: i32,
+    pub gap_extend: i32,
+    matrix: SymbolMap<T, SymbolMap<T, i32>>,
+}
+
+impl<T: Eq + Hash> PartialEq for Scoring<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.matched == other.matched
+            && self.mismatched == other.mismatched
+            && self.gap_open == other.gap_open
+            && self.gap_extend == other.gap_extend
+            && self.matrix == other.matrix
+    }
+}
+
+impl<T> Default for Scoring<T> {
+    fn default() -> Self {
+        Scoring::new(1, -1, -2, -1)
+    }
+}
+
+impl<T> Scoring<T> {
+    pub fn new(matched: i32, mismatched: i32, gap_open: i32, gap_extend: i32) -> Self {
+        Scoring {
+            matched,
+            mismatched,
+            gap_open,
+            gap_extend,
+            matrix: SymbolMap::new(),
+        }
+    }
+}
+
+impl<T: Eq + Hash + Clone> Scoring<T> {
+    /// Set the substitution score between symbols `a` and `b` (in both directions),
+    /// e.g. to load a BLOSUM or PAM matrix for peptide sequences
+    pub fn with_score(mut self, a: T, b: T, score: i32) -> Self {
+        for (x, y) in [(a.clone(), b.clone()), (b, a)] {
+            self.matrix.entry(x).or_default().insert(y, score);
+        }
+        self
+    }
+}
+
+impl<T: Eq + Hash> Scoring<T> {
+    /// Substitution score for aligning symbol `a` against symbol `b`
+    pub fn substitution(&self, a: &T, b: &T) -> i32 {
+        match self.matrix.get(a).and_then(|row| row.get(b)) {
+            Some(&score) => score,
+            None if a == b => self.matched,
+            None => self.mismatched,
+        }
+    }
+}
+
+/// A single alignment column, named after its CIGAR operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlignOp {
+    /// Equal tokens (`=`)
+    Match,
+    /// Different tokens (`X`)
+    Mismatch,
+    /// Token present in `b` only (`I`)
+    Insertion,
+    /// Token present in `a` only (`D`)
+    Deletion,
+}
+
+impl AlignOp {
+    /// Extended CIGAR code of the operation
+    pub fn code(self) -> char {
+        match self {
+            AlignOp::Match => '=',
+            AlignOp::Mismatch => 'X',
+            AlignOp::Insertion => 'I',
+            AlignOp::Deletion => 'D',
+        }
+    }
+}
+
+/// Result of a global or local sequence alignment of `a` against `b`
+///
+/// `ops` is the run-length encoded list of CIGAR operations.
+/// `start` and `end` are the token offsets in `a` and `b` covered by the alignment,
+/// which span both sequences entirely for global alignments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alignment {
+    pub score: i32,
+    pub ops: Vec<(usize, AlignOp)>,
+    pub start: (usize, usize),
+    pub end: (usize, usize),
+}
+
+impl Alignment {
+    /// CIGAR string of the alignment, e.g. `3=1X2I`
+    pub fn cigar(&self) -> String {
+        self.ops
+            .iter()
+            .map(|(n, op)| format!("{}{}", n, op.code()))
+            .collect()
+    }
+
+    /// The aligned parts of `a` and `b` with `-` filling the gaps
+    ///
+    /// `a` and `b` must be the sequences that were aligned. Multi-character tokens
+    /// are padded so that every column lines up.
+    pub fn aligned<T: Display>(&self, a: &[T], b: &[T]) -> (String, String) {
+        let (mut i, mut j) = self.start;
+        self.ops
+            .iter()
+            .flat_map(|&(n, op)| std::iter::repeat_n(op, n))
+            .fold((String::new(), String::new()), |(mut x, mut y), op| {
+                let (p, q) = match op {
+                    AlignOp::Match | AlignOp::Mismatch => (Some(&a[i]), Some(&b[j])),
+                    AlignOp::Deletion => (Some(&a[i]), None),
+                    AlignOp::Insertion => (None, Some(&b[j])),
+                };
+                i += p.is_some() as usize;
+                j += q.is_some() as usize;
+
+                let (p, q) = (p.map(T::to_string), q.map(T::to_string));
+                let width = std::cmp::max(
+                    p.as_ref().map_or(0, |t| t.chars().count()),
+                    q.as_ref().map_or(0, |t| t.chars().count()),
+                );
+                let gap = "-".repeat(width);
+                x.push_str(&format!("{:<width$}", p.as_ref().unwrap_or(&gap)));
+                y.push_str(&format!("{:<width$}", q.as_ref().unwrap_or(&gap)));
+                (x, y)
+            })
+    }
+}
+
+const NEG_INF: i32 = i32::MIN / 4;
+
+/// Alignment state a DP cell ends in: aligned pair, gap in `b`, gap in `a`,
+/// or the (local) start of the alignment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Pair,
+    Deletion,
+    Insertion,
+    Start,
+}
+
+/// Best of a list of (score, predecessor) candidates, preferring earlier entries on ties
+fn best(candidates: &[(i32, State)]) -> (i32, State) {
+    candidates
+        .iter()
+        .skip(1)
+        .fold(candidates[0], |acc, &c| if c.0 > acc.0 { c } else { acc })
+}
+
+/// Gotoh's affine gap alignment, global (Needleman-Wunsch) or local (Smith-Waterman)
+fn gotoh<T: Eq + Hash>(a: &[T], b: &[T], scoring: &Scoring<T>, local: bool) -> Alignment {
+    let (m, n) = (a.len(), b.len());
+    let (open, extend) = (scoring.gap_open, scoring.gap_extend);
+
+    let mut pair = vec![vec![(NEG_INF, State::Start); n + 1]; m + 1];
+    let mut del = vec![vec![(NEG_INF, State::Start); n + 1]; m + 1];
+    let mut ins = vec![vec![(NEG_INF, State::Start); n + 1]; m + 1];
+
+    pair[0][0] = (0, State::Start);
+    if !local {
+        for i in 1..=m {
+            del[i][0] = best(&[
+                (pair[i - 1][0].0 + open, State::Pair),
+                (del[i - 1][0].0 + extend, State::Deletion),
+            ]);
+        }
+        for j in 1..=n {
+            ins[0][j] = best(&[
+                (pair[0][j - 1].0 + open, State::Pair),
+                (ins[0][j - 1].0 + extend, State::Insertion),
+            ]);
+        }
+    }
+
+    for i in 1..=m {
+        for j in 1..=n {
+            let s = scoring.substitution(&a[i - 1], &b[j - 1]);
+            let mut candidates = vec![
+                (pair[i - 1][j - 1].0 + s, State::Pair),
+                (del[i - 1][j - 1].0 + s, State::Deletion),
+                (ins[i - 1][j - 1].0 + s, State::Insertion),
+            ];
+            if local {
+                candidates.push((s, State::Start));
+            }
+            pair[i][j] = best(&candidates);
+            del[i][j] = best(&[
+                (pair[i - 1][j].0 + open, State::Pair),
+                (del[i - 1][j].0 + extend, State::Deletion),
+                (ins[i - 1][j].0 + open, State::Insertion),
+            ]);
+            ins[i][j] = best(&[
+                (pair[i][j - 1].0 + open, State::Pair),
+                (ins[i][j - 1].0 + extend, State::Insertion),
+                (del[i][j - 1].0 + open, State::Deletion),
+            ]);
+        }
+    }
+
+    let (score, mut state, end) = if local {
+        (0..=m).flat_map(|i| (0..=n).map(move |j| (i, j))).fold(
+            (0, State::Start, (0, 0)),
+            |acc, (i, j)| {
+                if pair[i][j].0 > acc.0 {
+                    (pair[i][j].0, State::Pair, (i, j))
+                } else {
+                    acc
+                }
+            },
+        )
+    } else {
+        let (score, state) = best(&[
+            (pair[m][n].0, State::Pair),
+            (del[m][n].0, State::Deletion),
+            (ins[m][n].0, State::Insertion),
+        ]);
+        (score, state, (m, n))
+    };
+
+    let (mut i, mut j) = end;
+    let mut columns = Vec::new();
+    while state != State::Start && (i > 0 || j > 0) {
+        state = match state {
+            State::Pair => {
+                columns.push(if a[i - 1] == b[j - 1] {
+                    AlignOp::Match
+                } else {
+                    AlignOp::Mismatch
+                });
+                i -= 1;
+                j -= 1;
+                pair[i + 1][j + 1].1
+            }
+            State::Deletion => {
+                columns.push(AlignOp::Deletion);
+                i -= 1;
+                del[i + 1][j].1
+            }
+            State::Insertion => {
+                columns.push(AlignOp::Insertion);
+                j -= 1;
+                ins[i][j + 1].1
+            }
+            State::Start => unreachable!(),
+        };
+    }
+
+    let ops = columns
+        .iter()
+        .rev()
+        .fold(Vec::new(), |mut acc: Vec<(usize, AlignOp)>, &op| {
+            match acc.last_mut() {
+                Some((count, last)) if *last == op => *count += 1,
+                _ => acc.push((1, op)),
+            }
+            acc
+        });
+
+    Alignment {
+        score,
+        ops,
+        start: (i, j),
+        end,
+    }
+}
+
+/// Global alignment of two sequences (Needleman-Wunsch with affine gaps)
+///
+/// Generic counterpart of `needleman_wunsch`, for any sequence of symbols that can be
+/// hashed (for the scoring matrix). Printing the aligned sequences with
+/// `Alignment::aligned` additionally needs `Display`.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::{_needleman_wunsch, Scoring};
+/// let scoring = Scoring::new(5, -4, -10, -1).with_score('I', 'L', 2);
+/// let (a, b) = (['M', 'K', 'I', 'V'], ['M', 'L', 'V']);
+/// let alignment = _needleman_wunsch(&a, &b, &scoring);
+/// assert_eq!(alignment.score, 2);
+/// assert_eq!(alignment.aligned(&a, &b), ("MKIV".to_string(), "M-LV".to_string()));
+///
+/// #[derive(PartialEq, Eq, Hash)]
+/// enum Step { Protect, Couple, Deprotect, Cleave }
+/// use Step::*;
+/// let route1 = [Protect, Couple, Deprotect, Couple, Cleave];
+/// let route2 = [Protect, Couple, Deprotect, Cleave];
+/// assert_eq!(_needleman_wunsch(&route1, &route2, &Scoring::default()).cigar(), "3=1D1=");
+/// ```
+pub fn _needleman_wunsch<T: Eq + Hash>(a: &[T], b: &[T], scoring: &Scoring<T>) -> Alignment {
+    gotoh(a, b, scoring, false)
+}
+
+/// Local alignment of two sequences (Smith-Waterman with affine gaps)
+///
+/// Generic counterpart of `smith_waterman`.
+pub fn _smith_waterman<T: Eq + Hash>(a: &[T], b: &[T], scoring: &Scoring<T>) -> Alignment {
+    gotoh(a, b, scoring, true)
+}
+
+/// Global alignment of two token sequences (Needleman-Wunsch with affine gaps)
+///
+/// See `similarity_metrics::tokenize` for SMILES and SELFIES tokenizers.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::{needleman_wunsch_tokens, Scoring};
+/// use similarity_metrics::tokenize::smiles;
+/// let (a, b) = (smiles("CCCl"), smiles("CCBr"));
+/// let alignment = needleman_wunsch_tokens(&a, &b, &Scoring::default());
+/// assert_eq!(alignment.score, 1);
+/// assert_eq!(alignment.cigar(), "2=1X");
+/// assert_eq!(alignment.aligned(&a, &b), ("CCCl".to_string(), "CCBr".to_string()));
+///
+/// let (a, b) = (smiles("OCCl"), smiles("OCC(C)Cl"));
+/// let alignment = needleman_wunsch_tokens(&a, &b, &Scoring::default());
+/// assert_eq!(alignment.cigar(), "2=4I1=");
+/// assert_eq!(alignment.aligned(&a, &b), ("OC----Cl".to_string(), "OCC(C)Cl".to_string()));
+/// ```
+pub fn needleman_wunsch_tokens(a: &[&str], b: &[&str], scoring: &Scoring<&str>) -> Alignment {
+    _needleman_wunsch(a, b, scoring)
+}
+
+/// Local alignment of two token sequences (Smith-Waterman with affine gaps)
+///
+/// Only the highest-scoring pair of subsequences is aligned;
+/// `start` and `end` give its position in `a` and `b`.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::{smith_waterman_tokens, Scoring};
+/// use similarity_metrics::tokenize::selfies;
+/// let (a, b) = (selfies("[N][C][C][=O][O]"), selfies("[Cl][C][C][=O][Br]"));
+/// let alignment = smith_waterman_tokens(&a, &b, &Scoring::default());
+/// assert_eq!(alignment.score, 3);
+/// assert_eq!(alignment.cigar(), "3=");
+/// assert_eq!(alignment.aligned(&a, &b), ("[C][C][=O]".to_string(), "[C][C][=O]".to_string()));
+/// assert_eq!((alignment.start, alignment.end), ((1, 1), (4, 4)));
+/// ```
+pub fn smith_waterman_tokens(a: &[&str], b: &[&str], scoring: &Scoring<&str>) -> Alignment {
+    _smith_waterman(a, b, scoring)
+}
+
+/// Global alignment of two strings (Needleman-Wunsch with affine gaps)
+///
+/// Given two strings `a` and `b`, find the highest-scoring alignment of the whole of `a`
+/// against the whole of `b` under `scoring`, comparing single characters.
+/// Use `needleman_wunsch_tokens` to align SMILES or SELFIES tokens instead.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::{needleman_wunsch, AlignOp, Scoring};
+/// use similarity_metrics::tokenize::chars;
+/// let alignment = needleman_wunsch("GATTACA", "GCATGCU", &Scoring::new(1, -1, -1, -1));
+/// assert_eq!(alignment.score, 0);
+/// let (x, y) = alignment.aligned(&chars("GATTACA"), &chars("GCATGCU"));
+/// assert_eq!(x.len(), y.len());
+///
+/// let alignment = needleman_wunsch("ACGTTTTACG", "ACGACG", &Scoring::new(2, -1, -3, -1));
+/// assert_eq!(alignment.score, 6);
+/// assert_eq!(
+///     alignment.aligned(&chars("ACGTTTTACG"), &chars("ACGACG")),
+///     ("ACGTTTTACG".to_string(), "ACG----ACG".to_string())
+/// );
+/// assert_eq!(alignment.ops, [(3, AlignOp::Match), (4, AlignOp::Deletion), (3, AlignOp::Match)]);
+///
+/// let blosum = Scoring::new(4, -2, -11, -1).with_score("I", "L", 2).with_score("K", "R", 2);
+/// let alignment = needleman_wunsch("KIL", "RLL", &blosum);
+/// assert_eq!(alignment.score, 8);
+/// assert_eq!(alignment.cigar(), "2X1=");
+///
+/// let alignment = needleman_wunsch("", "ABC", &Scoring::default());
+/// assert_eq!(alignment.score, -4);
+/// assert_eq!(alignment.aligned(&chars(""), &chars("ABC")), ("---".to_string(), "ABC".to_string()));
+/// ```
+pub fn needleman_wunsch(a: &str, b: &str, scoring: &Scoring<&str>) -> Alignment {
+    needleman_wunsch_tokens(&tokenize::chars(a), &tokenize::chars(b), scoring)
+}
+
+/// Local alignment of two strings (Smith-Waterman with affine gaps)
+///
+/// Given two strings `a` and `b`, find the highest-scoring alignment between any substring
+/// of `a` and any substring of `b` under `scoring`, comparing single characters.
+/// Use `smith_waterman_tokens` to align SMILES or SELFIES tokens instead.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::{smith_waterman, Scoring};
+/// use similarity_metrics::tokenize::chars;
+/// let alignment = smith_waterman("TGTTACGG", "GGTTGACTA", &Scoring::new(3, -3, -2, -2));
+/// assert_eq!(alignment.score, 13);
+/// assert_eq!(
+///     alignment.aligned(&chars("TGTTACGG"), &chars("GGTTGACTA")),
+///     ("GTT-AC".to_string(), "GTTGAC".to_string())
+/// );
+/// assert_eq!(alignment.cigar(), "3=1I2=");
+/// assert_eq!((alignment.start, alignment.end), ((1, 1), (6, 7)));
+///
+/// let alignment = smith_waterman("AAA", "CCC", &Scoring::default());
+/// assert_eq!(alignment.score, 0);
+/// assert!(alignment.ops.is_empty());
+/// ```
+pub fn smith_waterman(a: &str, b: &str, scoring: &Scoring<&str>) -> Alignment {
+    smith_waterman_tokens(&tokenize::chars(a), &tokenize::chars(b), scoring)
+}
+
+/// Smith-Waterman-Gotoh similarity between two sequences, in [0, 1]
+///
+/// Local alignment score divided by the best possible score,
+/// i.e. the shorter sequence matched in full.
+/// Returns 0 if either sequence is empty.
+pub fn _smith_waterman_gotoh<T: Eq + Hash>(a: &[T], b: &[T], scoring: &Scoring<T>) -> f64 {
+    let best = std::cmp::min(a.len(), b.len()) as f64 * scoring.matched as f64;
+    if best <= 0.0 {
+        0.0
+    } else {
+        (_smith_waterman(a, b, scoring).score as f64 / best).clamp(0.0, 1.0)
+    }
+}
+
+/// Smith-Waterman-Gotoh similarity between two strings, in [0, 1]
+///
+/// Given two strings `a` and `b`, score their best local alignment under `scoring`
+/// and normalise it by the score of a perfect match of the shorter string.
+///
+/// 1 indicates that one string contains the other.
+/// 0 indicates that there is no similarity between `a` and `b`.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::{smith_waterman_gotoh, Scoring};
+/// let scoring = Scoring::default();
+/// assert_eq!(smith_waterman_gotoh("aspirin", "aspirin tablets", &scoring), 1.0);
+/// assert_eq!(smith_waterman_gotoh("paracetamol", "paracetamole", &scoring), 1.0);
+/// assert_eq!(smith_waterman_gotoh("ibuprofen", "ibuprophen", &scoring), 6.0 / 9.0);
+/// assert_eq!(smith_waterman_gotoh("abc", "xyz", &scoring), 0.0);
+/// assert_eq!(smith_waterman_gotoh("", "", &scoring), 0.0);
+/// ```
+pub fn smith_waterman_gotoh(a: &str, b: &str, scoring: &Scoring<&str>) -> f64 {
+    _smith_waterman_gotoh(&tokenize::chars(a), &tokenize::chars(b), scoring)
+}
+
+/// Position in `a`, position in `b` and length of the longest common substring
+/// of `a` and `b`, preferring the earliest one in `a` and then in `b` on ties
+fn longest_common_substring<T: PartialEq>(a: &[T], b: &[T]) -> (usize, usize, usize) {
+    let mut prev_row = vec![0; b.len() + 1];
+    let mut curr_row = vec![0; b.len() + 1];
+    let mut best = (0, 0, 0);
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            curr_row[j] = if a[i - 1] == b[j - 1] {
+                prev_row[j - 1] + 1
+            } else {
+                0
+            };
+            if curr_row[j] > best.2 {
+                best = (i - curr_row[j], j - curr_row[j], curr_row[j]);
+            }
+        }
+        std::mem::swap(&mut prev_row, &mut curr_row);
+    }
+
+    best
+}
+
+/// Ratcliff-Obershelp (gestalt pattern matching) similarity between two sequences, in [0, 1]
+///
+/// Twice the number of matching symbols divided by the total number of symbols, where
+/// matching symbols are found by taking the longest common substring and recursing on
+/// the pieces to its left and right.
+/// Returns 0 if both sequences are empty.
+pub fn _ratcliff_obershelp<T: PartialEq>(a: &[T], b: &[T]) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 0.0;
+    }
+
+    let mut matching = 0;
+    let mut stack = vec![(a, b)];
+    while let Some((x, y)) = stack.pop() {
+        let (i, j, len) = longest_common_substring(x, y);
+        if len > 0 {
+            matching += len;
+            stack.push((&x[..i], &y[..j]));
+            stack.push((&x[i + len..], &y[j + len..]));
+        }
+    }
+
+    (2 * matching) as f64 / (a.len() + b.len()) as f64
+}
+
+/// Ratcliff-Obershelp (gestalt pattern matching) similarity between two strings, in [0, 1]
+///
+/// Equivalent to `difflib.SequenceMatcher(None, a, b, autojunk=False).ratio()` in Python,
+/// except that two empty strings have similarity 0.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::ratcliff_obershelp;
+/// assert_eq!(ratcliff_obershelp("WIKIMEDIA", "WIKIMANIA"), 2.0 * 7.0 / 18.0);
+/// assert_eq!(ratcliff_obershelp("GESTALT PATTERN MATCHING", "GESTALT PRACTICE"), 2.0 * 12.0 / 40.0);
+/// assert_eq!(ratcliff_obershelp("caffeine", "caffeine"), 1.0);
+/// assert_eq!(ratcliff_obershelp("abc", "xyz"), 0.0);
+/// assert_eq!(ratcliff_obershelp("", ""), 0.0);
+/// ```
+pub fn ratcliff_obershelp(a: &str, b: &str) -> f64 {
+    let _a = a.chars().collect::<Vec<char>>();
+    let _b = b.chars().collect::<Vec<char>>();
+
+    _ratcliff_obershelp(&_a, &_b)
+}
+
+/// Monge-Elkan similarity between two token sequences, in [0, 1]
+///
+/// For each token of `a`, take its best `similarity` to any token of `b`, and average.
+/// The measure is not symmetric; average `_monge_elkan(a, b, ..)` and
+/// `_monge_elkan(b, a, ..)` for a symmetric variant.
+/// Returns 0 if either sequence is empty.
+pub fn _monge_elkan<T, F: Fn(&T, &T) -> f64>(a: &[T], b: &[T], similarity: F) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    a.iter()
+        .map(|x| b.iter().map(|y| similarity(x, y)).fold(0.0, f64::max))
+        .sum::<f64>()
+        / a.len() as f64
+}
+
+/// Monge-Elkan similarity between two multi-word names, in [0, 1]
+///
+/// Both strings are split on whitespace and words are compared with Jaro–Winkler
+/// (prefix weight 0.1), so word order and extra words matter less than with
+/// character-level measures.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::monge_elkan;
+/// assert_eq!(monge_elkan("acetylsalicylic acid", "acid acetylsalicylic"), 1.0);
+/// assert_eq!(monge_elkan("sodium chloride", "sodium chloride solution"), 1.0);
+/// assert!(monge_elkan("sodium chloride solution", "sodium chloride") < 1.0);
+/// assert!(monge_elkan("ethyl alcohol", "ethanol") > monge_elkan("ethyl alcohol", "benzene"));
+/// assert_eq!(monge_elkan("", "water"), 0.0);
+/// ```
+pub fn monge_elkan(a: &str, b: &str) -> f64 {
+    let _a = a.split_whitespace().collect::<Vec<&str>>();
+    let _b = b.split_whitespace().collect::<Vec<&str>>();
+
+    _monge_elkan(&_a, &_b, |x, y| JARO_WINKLER(x, y, None))
+}
+
+/// Sørensen-Dice coefficient between the character bigrams of two strings, in [0, 1]
+///
+/// Bigrams are counted with multiplicity: twice the number of shared bigrams divided by
+/// the total number of bigrams. Returns 0 if either string is shorter than two characters.
+/// See `similarity_metrics::qgram` for other q-gram sizes and set-based measures.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::sorensen_dice;
+/// assert_eq!(sorensen_dice("night", "nacht"), 0.25);
+/// assert_eq!(sorensen_dice("methanol", "methanol"), 1.0);
+/// assert_eq!(sorensen_dice("aaaa", "aa"), 0.5);
+/// assert_eq!(sorensen_dice("a", "a"), 0.0);
+/// ```
+pub fn sorensen_dice(a: &str, b: &str) -> f64 {
+    sorensen_dice_tokens(&tokenize::chars(a), &tokenize::chars(b))
+}
+
+/// Sørensen-Dice coefficient between the token bigrams of two token sequences, in [0, 1]
+///
+/// Token counterpart of `similarity_metrics::dist::sorensen_dice`.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::sorensen_dice_tokens;
+/// use similarity_metrics::tokenize::smiles;
+/// assert_eq!(sorensen_dice_tokens(&smiles("CCCl"), &smiles("CCBr")), 0.5);
+/// ```
+pub fn sorensen_dice_tokens(a: &[&str], b: &[&str]) -> f64 {
+    let (p1, p2) = (qgram::profile(a, 2), qgram::profile(b, 2));
+    let total = p1.values().sum::<usize>() + p2.values().sum::<usize>();
+    let shared = p1
+        .iter()
+        .filter_map(|(gram, &n)| p2.get(gram).map(|&m| std::cmp::min(n, m)))
+        .sum::<usize>();
+
+    if total == 0 {
+        0.0
+    } else {
+        (2 * shared) as f64 / total as f64
+    }
+}
+
+/// Symmetric matrix of `distance` between every pair of `items`
+///
+/// Rows are computed in parallel; only the upper triangle (including the diagonal) is
+/// evaluated and then mirrored, so `distance` should be symmetric.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::{_pairwise, l_distance_tokens};
+/// use similarity_metrics::tokenize::smiles;
+/// let library = ["CCCl", "CCBr", "CCO"].map(smiles);
+/// let matrix = _pairwise(&library, |a, b| l_distance_tokens(a, b));
+/// assert_eq!(matrix, ndarray::arr2(&[[0, 1, 1], [1, 0, 1], [1, 1, 0]]));
+/// ```
+pub fn _pairwise<T, D, F>(items: &[T], distance: F) -> Array2<D>
+where
+    T: Sync,
+    D: Copy + Default + Send,
+    F: Fn(&T, &T) -> D + Sync,
+{
+    let n = items.len();
+    let rows = (0..n)
+        .into_par_iter()
+        .map(|i| (i..n).map(|j| distance(&items[i], &items[j])).collect())
+        .collect::<Vec<Vec<D>>>();
+
+    let mut matrix = Array2::default((n, n));
+    for (i, row) in rows.into_iter().enumerate() {
+        for (j, d) in (i..n).zip(row) {
+            matrix[[i, j]] = d;
+            matrix[[j, i]] = d;
+        }
+    }
+    matrix
+}
+
+/// Matrix of `distance` from each of `queries` (rows) to each of `library` (columns)
+///
+/// Rows are computed in parallel.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::{_cross, lcs_tokens};
+/// use similarity_metrics::tokenize::smiles;
+/// let queries = ["CCCl"].map(smiles);
+/// let library = ["CCBr", "CCCCl", "O"].map(smiles);
+/// let matrix = _cross(&queries, &library, |a, b| lcs_tokens(a, b));
+/// assert_eq!(matrix, ndarray::arr2(&[[2, 3, 0]]));
+/// ```
+pub fn _cross<T, D, F>(queries: &[T], library: &[T], distance: F) -> Array2<D>
+where
+    T: Sync,
+    D: Copy + Default + Send,
+    F: Fn(&T, &T) -> D + Sync,
+{
+    let rows = queries
+        .par_iter()
+        .flat_map_iter(|q| library.iter().map(|t| distance(q, t)).collect::<Vec<D>>())
+        .collect::<Vec<D>>();
+
+    Array2::from_shape_vec((queries.len(), library.len()), rows).unwrap()
+}
+
+/// Symmetric matrix of a string distance between every pair of strings in `items`
+///
+/// Works with any `&str` function in this module, e.g. `l_distance`, `lcs` or `JARO`.
+/// See `_pairwise` for token sequences and other symbols.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::{l_distance, pairwise, JARO};
+/// let library = ["CCO", "CCN", "CCCO"];
+/// assert_eq!(
+///     pairwise(&library, l_distance),
+///     ndarray::arr2(&[[0, 1, 1], [1, 0, 2], [1, 2, 0]])
+/// );
+/// let similarity = pairwise(&library, JARO);
+/// assert!(similarity.diag().iter().all(|&s| s == 1.0));
+/// assert_eq!(similarity[[0, 2]], similarity[[2, 0]]);
+/// ```
+pub fn pairwise<S, D>(items: &[S], distance: fn(&str, &str) -> D) -> Array2<D>
+where
+    S: AsRef<str> + Sync,
+    D: Copy + Default + Send,
+{
+    _pairwise(items, |a, b| distance(a.as_ref(), b.as_ref()))
+}
+
+/// Matrix of a string distance from each of `queries` (rows) to each of `library` (columns)
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::{cross, l_distance};
+/// let library = vec!["CCO".to_string(), "CCN".to_string(), "c1ccccc1".to_string()];
+/// assert_eq!(
+///     cross(&["CCO".to_string(), "CC".to_string()], &library, l_distance),
+///     ndarray::arr2(&[[0, 1, 8], [1, 1, 8]])
+/// );
+/// ```
+pub fn cross<S, D>(queries: &[S], library: &[S], distance: fn(&str, &str) -> D) -> Array2<D>
+where
+    S: AsRef<str> + Sync,
+    D: Copy + Default + Send,
+{
+    _cross(queries, library, |a, b| distance(a.as_ref(), b.as_ref()))
+}
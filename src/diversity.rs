@@ -0,0 +1,111 @@
+use crate::cluster::{seeded_rng, Leaders, Metric};
+use rand::Rng;
+
+/// Pick `n` diverse fingerprints with the MaxMin algorithm, starting from one drawn
+/// with `seed`
+///
+/// Returns the indices of the picks in the order they were made; see `maxmin_extend`.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::diversity::maxmin_pick;
+/// use similarity_metrics::measures::soergel;
+/// let fps = vec![
+///     vec![1, 1, 1, 0, 0, 0],
+///     vec![1, 1, 0, 0, 0, 0],
+///     vec![0, 0, 0, 1, 1, 1],
+///     vec![0, 0, 0, 1, 1, 0],
+/// ];
+/// let picks = maxmin_pick(&fps, 2, 0, soergel);
+/// assert_eq!(picks.len(), 2);
+/// assert_ne!(picks[0] < 2, picks[1] < 2);
+/// ```
+pub fn maxmin_pick(fps: &[Vec<u8>], n: usize, seed: u64, metric: Metric) -> Vec<usize> {
+    maxmin_extend(fps, &[], n, seed, metric)
+}
+
+/// Extend the selection `picked` to `n` fingerprints with the MaxMin algorithm
+///
+/// Each new pick is the fingerprint whose nearest pick is furthest away under `metric`,
+/// the earliest on ties. With an empty selection the first pick is drawn with `seed`.
+/// As in RDKit's lazy picker, the distance matrix is never built: every candidate keeps
+/// its distance to the nearest pick, and is only compared with newer picks while that
+/// distance can still beat the best candidate found so far.
+///
+/// Returns `picked` followed by the new picks, until there are `n` or no fingerprints
+/// are left.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::diversity::maxmin_extend;
+/// use similarity_metrics::measures::soergel;
+/// let fps = vec![
+///     vec![1, 1, 1, 0, 0, 0],
+///     vec![1, 1, 0, 0, 0, 0],
+///     vec![0, 0, 0, 1, 1, 1],
+///     vec![1, 0, 0, 0, 0, 1],
+/// ];
+/// assert_eq!(maxmin_extend(&fps, &[0], 3, 0, soergel), [0, 2, 3]);
+/// ```
+pub fn maxmin_extend(
+    fps: &[Vec<u8>],
+    picked: &[usize],
+    n: usize,
+    seed: u64,
+    metric: Metric,
+) -> Vec<usize> {
+    let mut picks = picked.to_vec();
+    if picks.is_empty() && n > 0 && !fps.is_empty() {
+        picks.push(seeded_rng(seed).gen_range(0..fps.len()));
+    }
+
+    // Index, distance to the nearest pick compared so far, and number of picks compared
+    let mut candidates = (0..fps.len())
+        .filter(|i| !picks.contains(i))
+        .map(|i| (i, f64::INFINITY, 0))
+        .collect::<Vec<_>>();
+
+    while picks.len() < n && !candidates.is_empty() {
+        let mut best = 0;
+        let mut best_distance = f64::NEG_INFINITY;
+        for (position, (i, distance, compared)) in candidates.iter_mut().enumerate() {
+            while *compared < picks.len() && *distance > best_distance {
+                *distance = distance.min(metric(&fps[*i], &fps[picks[*compared]]));
+                *compared += 1;
+            }
+            if *compared == picks.len() && *distance > best_distance {
+                best = position;
+                best_distance = *distance;
+            }
+        }
+        picks.push(candidates.remove(best).0);
+    }
+
+    picks
+}
+
+/// Pick fingerprints by sphere exclusion: in input order, every fingerprint further
+/// than `radius` under `metric` from all earlier picks is picked
+///
+/// Returns the indices of the picks, which are the leaders of `cluster::Leaders`.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::diversity::sphere_exclusion_pick;
+/// use similarity_metrics::measures::soergel;
+/// let fps = vec![
+///     vec![1, 1, 1, 0, 0, 0],
+///     vec![1, 1, 0, 0, 0, 0],
+///     vec![0, 0, 0, 1, 1, 1],
+///     vec![0, 0, 0, 1, 1, 0],
+/// ];
+/// assert_eq!(sphere_exclusion_pick(&fps, 0.4, soergel), [0, 2]);
+/// ```
+pub fn sphere_exclusion_pick(fps: &[Vec<u8>], radius: f64, metric: Metric) -> Vec<usize> {
+    let mut leaders = Leaders::new(radius, metric);
+    leaders.add(fps);
+    leaders.origins().to_vec()
+}
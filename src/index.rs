@@ -0,0 +1,326 @@
+use crate::dist::{l_distance, l_distance_bounded};
+use std::collections::BTreeMap;
+
+/// Insert `(distance, index)` into a sorted list of the `k` best candidates
+fn keep_nearest<D: PartialOrd + Copy>(best: &mut Vec<(D, usize)>, candidate: (D, usize), k: usize) {
+    let at =
+        best.partition_point(|&(d, i)| d < candidate.0 || (d == candidate.0 && i < candidate.1));
+    if at < k {
+        best.insert(at, candidate);
+        best.truncate(k);
+    }
+}
+
+/// Integer-valued distance between two items
+pub type Distance<T> = fn(&T, &T) -> usize;
+
+/// Integer-valued distance that gives up (`None`) once it exceeds the given bound
+pub type BoundedDistance<T> = fn(&T, &T, usize) -> Option<usize>;
+
+#[derive(Debug, Clone)]
+struct BkNode {
+    children: BTreeMap<usize, usize>,
+}
+
+/// Burkhard-Keller tree over an integer-valued metric
+///
+/// Works with any distance that obeys the triangle inequality, such as
+/// `l_distance` or `l_distance_tokens`. Items are identified by their insertion order.
+/// If a bounded variant of the distance is given, radius queries use it to give up early
+/// on subtrees that cannot contain a match.
+#[derive(Debug, Clone)]
+pub struct BkTree<T> {
+    items: Vec<T>,
+    nodes: Vec<BkNode>,
+    distance: Distance<T>,
+    bounded: Option<BoundedDistance<T>>,
+}
+
+impl BkTree<String> {
+    /// Empty tree over Levenshtein distance between strings,
+    /// using `l_distance_bounded` for radius queries
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use similarity_metrics::index::BkTree;
+    /// let mut tree = BkTree::levenshtein();
+    /// tree.extend(["CCO", "CCN", "CCCO", "c1ccccc1", "OCC"].map(String::from));
+    /// assert_eq!(tree.within(&"CCO".to_string(), 1), [(0, 0), (1, 1), (2, 1)]);
+    /// assert_eq!(tree.nearest(&"CCCl".to_string(), 2), [(2, 1), (0, 2)]);
+    /// ```
+    pub fn levenshtein() -> Self {
+        BkTree::with_bound(
+            |a, b| l_distance(a, b),
+            |a, b, k| l_distance_bounded(a, b, k),
+        )
+    }
+}
+
+impl<T> BkTree<T> {
+    pub fn new(distance: Distance<T>) -> Self {
+        BkTree {
+            items: Vec::new(),
+            nodes: Vec::new(),
+            distance,
+            bounded: None,
+        }
+    }
+
+    /// Empty tree whose radius queries use `bounded(a, b, k)`, which must return
+    /// `distance(a, b)` if it is at most `k` and `None` otherwise
+    pub fn with_bound(distance: Distance<T>, bounded: BoundedDistance<T>) -> Self {
+        BkTree {
+            bounded: Some(bounded),
+            ..BkTree::new(distance)
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Item with insertion index `i`
+    pub fn get(&self, i: usize) -> Option<&T> {
+        self.items.get(i)
+    }
+
+    /// Add an item, returning its index
+    pub fn insert(&mut self, item: T) -> usize {
+        let index = self.items.len();
+
+        if index > 0 {
+            let mut node = 0;
+            loop {
+                let d = (self.distance)(&item, &self.items[node]);
+                match self.nodes[node].children.get(&d) {
+                    Some(&child) => node = child,
+                    None => {
+                        self.nodes[node].children.insert(d, index);
+                        break;
+                    }
+                }
+            }
+        }
+
+        self.items.push(item);
+        self.nodes.push(BkNode {
+            children: BTreeMap::new(),
+        });
+        index
+    }
+
+    pub fn extend<I: IntoIterator<Item = T>>(&mut self, items: I) {
+        for item in items {
+            self.insert(item);
+        }
+    }
+
+    /// All items within distance `radius` of `query`,
+    /// as `(index, distance)` pairs sorted by distance and then index
+    pub fn within(&self, query: &T, radius: usize) -> Vec<(usize, usize)> {
+        let mut found = Vec::new();
+        let mut stack = if self.is_empty() { vec![] } else { vec![0] };
+
+        while let Some(node) = stack.pop() {
+            let children = &self.nodes[node].children;
+            let reach = radius + children.keys().next_back().copied().unwrap_or(0);
+            let d = match self.bounded {
+                Some(bounded) => match bounded(query, &self.items[node], reach) {
+                    Some(d) => d,
+                    None => continue,
+                },
+                None => (self.distance)(query, &self.items[node]),
+            };
+
+            if d <= radius {
+                found.push((node, d));
+            }
+            stack.extend(
+                children
+                    .range(d.saturating_sub(radius)..=d + radius)
+                    .map(|(_, &child)| child),
+            );
+        }
+
+        found.sort_by_key(|&(i, d)| (d, i));
+        found
+    }
+
+    /// The `k` items closest to `query`,
+    /// as `(index, distance)` pairs sorted by distance and then index
+    pub fn nearest(&self, query: &T, k: usize) -> Vec<(usize, usize)> {
+        let mut best = Vec::with_capacity(k + 1);
+        let mut stack = if self.is_empty() || k == 0 {
+            vec![]
+        } else {
+            vec![0]
+        };
+
+        while let Some(node) = stack.pop() {
+            let d = (self.distance)(query, &self.items[node]);
+            keep_nearest(&mut best, (d, node), k);
+
+            let tau = if best.len() < k {
+                usize::MAX
+            } else {
+                best[k - 1].0
+            };
+            stack.extend(
+                self.nodes[node]
+                    .children
+                    .range(d.saturating_sub(tau)..=d.saturating_add(tau))
+                    .map(|(_, &child)| child),
+            );
+        }
+
+        best.into_iter().map(|(d, i)| (i, d)).collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct VpNode {
+    index: usize,
+    mu: f64,
+    inside: Option<usize>,
+    outside: Option<usize>,
+}
+
+/// Vantage-point tree over a real-valued metric
+///
+/// Built once from a fixed set of items, e.g. with `1 - JARO` or `1 - jaro_tokens`
+/// as the distance. Items are identified by their position in the input.
+/// Results are exact only if the distance obeys the triangle inequality.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::JARO;
+/// use similarity_metrics::index::VpTree;
+/// let library = ["CCO", "CCN", "CCCO", "c1ccccc1", "OCC"].map(String::from);
+/// let tree = VpTree::new(library.to_vec(), |a, b| 1.0 - JARO(a, b));
+/// let hits = tree.within(&"CCO".to_string(), 0.2);
+/// assert_eq!(hits.iter().map(|&(i, _)| i).collect::<Vec<_>>(), [0, 2]);
+/// assert_eq!(tree.nearest(&"CCO".to_string(), 1), [(0, 0.0)]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct VpTree<T> {
+    items: Vec<T>,
+    nodes: Vec<VpNode>,
+    root: Option<usize>,
+    distance: fn(&T, &T) -> f64,
+}
+
+impl<T> VpTree<T> {
+    pub fn new(items: Vec<T>, distance: fn(&T, &T) -> f64) -> Self {
+        let mut tree = VpTree {
+            items,
+            nodes: Vec::new(),
+            root: None,
+            distance,
+        };
+        tree.root = tree.build((0..tree.items.len()).collect());
+        tree
+    }
+
+    /// Build the subtree over `indices`, using the first one as the vantage point
+    /// and splitting the rest at the median distance from it
+    fn build(&mut self, indices: Vec<usize>) -> Option<usize> {
+        let (&vantage, rest) = indices.split_first()?;
+
+        let mut by_distance = rest
+            .iter()
+            .map(|&i| ((self.distance)(&self.items[vantage], &self.items[i]), i))
+            .collect::<Vec<_>>();
+        by_distance.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mid = by_distance.len() / 2;
+        let mu = by_distance.get(mid).map_or(0.0, |&(d, _)| d);
+        let outside = by_distance.split_off(mid);
+
+        let node = self.nodes.len();
+        self.nodes.push(VpNode {
+            index: vantage,
+            mu,
+            inside: None,
+            outside: None,
+        });
+        self.nodes[node].inside = self.build(by_distance.into_iter().map(|(_, i)| i).collect());
+        self.nodes[node].outside = self.build(outside.into_iter().map(|(_, i)| i).collect());
+
+        Some(node)
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Item at position `i` of the input
+    pub fn get(&self, i: usize) -> Option<&T> {
+        self.items.get(i)
+    }
+
+    /// Walk the tree for `query`, calling `visit` with each visited item index and its
+    /// distance; `visit` returns the current search radius used to prune subtrees
+    fn search<F: FnMut(usize, f64) -> f64>(&self, query: &T, mut visit: F) {
+        let mut stack = self.root.into_iter().collect::<Vec<_>>();
+        while let Some(node) = stack.pop() {
+            let VpNode {
+                index,
+                mu,
+                inside,
+                outside,
+            } = self.nodes[node];
+            let d = (self.distance)(query, &self.items[index]);
+            let tau = visit(index, d);
+
+            if d - tau <= mu {
+                stack.extend(inside);
+            }
+            if d + tau >= mu {
+                stack.extend(outside);
+            }
+        }
+    }
+
+    /// All items within distance `radius` of `query`,
+    /// as `(index, distance)` pairs sorted by distance and then index
+    pub fn within(&self, query: &T, radius: f64) -> Vec<(usize, f64)> {
+        let mut found = Vec::new();
+        self.search(query, |i, d| {
+            if d <= radius {
+                found.push((i, d));
+            }
+            radius
+        });
+
+        found.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+        found
+    }
+
+    /// The `k` items closest to `query`,
+    /// as `(index, distance)` pairs sorted by distance and then index
+    pub fn nearest(&self, query: &T, k: usize) -> Vec<(usize, f64)> {
+        let mut best = Vec::with_capacity(k + 1);
+        if k > 0 {
+            self.search(query, |i, d| {
+                keep_nearest(&mut best, (d, i), k);
+                if best.len() < k {
+                    f64::INFINITY
+                } else {
+                    best[k - 1].0
+                }
+            });
+        }
+
+        best.into_iter().map(|(d, i)| (i, d)).collect()
+    }
+}
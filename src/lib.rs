@@ -1,4 +1,9 @@
 pub mod dist;
+pub mod diversity;
+pub mod index;
 pub mod load;
 pub mod measures;
 pub mod cluster;
+pub mod qgram;
+pub mod similarity;
+pub mod tokenize;
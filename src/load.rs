@@ -12,12 +12,18 @@ fn load_from_file(f: &str) -> Vec<Molecule> {
         .collect::<Vec<_>>()
 }
 
+/// Fingerprint every SMILES line of file `f`, one `u8` (0 or 1) per bit
 pub fn gen_fps(fp: Kind, f: &str) -> Vec<Vec<u8>> {
     let fpg = fingerprint::FingerprintGenerator::new(fp);
     let mols = load_from_file(f);
 
     mols.iter()
-        .map(|x| fpg.get_fingerprint(x).iter().map(|x| *x as u8).collect())
+        .map(|x| {
+            fpg.get_fingerprint(x)
+                .iter()
+                .flat_map(|word| (0..32).map(move |i| ((word >> i) & 1) as u8))
+                .collect()
+        })
         .collect()
 }
 
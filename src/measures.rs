@@ -56,6 +56,34 @@ pub fn forbes(f1: &[u8], f2: &[u8]) -> f64 {
 }
 
 pub fn soergel(f1: &[u8], f2: &[u8]) -> f64 {
-    let (a, b, c) = abc(f1, f2);
-    (a + b - 2 * c) as f64 / (a + b - c) as f64
+    match abc(f1, f2) {
+        (0, 0, _) => 0.0,
+        (a, b, c) => (a + b - 2 * c) as f64 / (a + b - c) as f64,
+    }
+}
+
+/// `1 - tanimoto`, for clustering and picking; two empty fingerprints are at distance 0
+pub fn tanimoto_distance(f1: &[u8], f2: &[u8]) -> f64 {
+    match abc(f1, f2) {
+        (0, 0, _) => 0.0,
+        (a, b, c) => 1.0 - c as f64 / (a + b - c) as f64,
+    }
+}
+
+/// `1 - dice`, for clustering and picking; two empty fingerprints are at distance 0
+pub fn dice_distance(f1: &[u8], f2: &[u8]) -> f64 {
+    match abc(f1, f2) {
+        (0, 0, _) => 0.0,
+        (a, b, c) => 1.0 - (2 * c) as f64 / (a + b) as f64,
+    }
+}
+
+/// `1 - cosine`, for clustering and picking; two empty fingerprints are at distance 0,
+/// and an empty fingerprint is at distance 1 from any other
+pub fn cosine_distance(f1: &[u8], f2: &[u8]) -> f64 {
+    match abc(f1, f2) {
+        (0, 0, _) => 0.0,
+        (0, _, _) | (_, 0, _) => 1.0,
+        (a, b, c) => 1.0 - c as f64 / (a as f64 * b as f64).sqrt(),
+    }
 }
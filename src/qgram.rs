@@ -0,0 +1,162 @@
+use std::collections::BTreeMap;
+
+/// Multiset of q-grams, mapping each q-gram to the number of times it occurs
+pub type Profile<'a> = BTreeMap<Vec<&'a str>, usize>;
+
+/// Count every window of `q` consecutive tokens
+///
+/// Tokens can come from any tokenizer in `similarity_metrics::tokenize`,
+/// e.g. `chars` for character q-grams or `smiles` for SMILES token n-grams.
+/// Sequences shorter than `q` have an empty profile.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::qgram::profile;
+/// use similarity_metrics::tokenize::{chars, smiles};
+/// let p = profile(&chars("CCCO"), 2);
+/// assert_eq!(p.len(), 2);
+/// assert_eq!(p[&vec!["C", "C"]], 2);
+/// assert_eq!(p[&vec!["C", "O"]], 1);
+///
+/// let p = profile(&smiles("CCl"), 2);
+/// assert_eq!(p.len(), 1);
+/// assert_eq!(p[&vec!["C", "Cl"]], 1);
+///
+/// assert!(profile(&chars("CO"), 3).is_empty());
+/// ```
+pub fn profile<'a>(tokens: &[&'a str], q: usize) -> Profile<'a> {
+    if q == 0 {
+        return Profile::new();
+    }
+
+    tokens.windows(q).fold(Profile::new(), |mut acc, gram| {
+        *acc.entry(gram.to_vec()).or_insert(0) += 1;
+        acc
+    })
+}
+
+/// 64-bit FNV-1a hash of a q-gram, separating tokens so that `["C", "l"]` and `["Cl"]` differ
+fn fnv1a(gram: &[&str]) -> u64 {
+    gram.iter()
+        .flat_map(|token| token.bytes().chain(std::iter::once(0xff)))
+        .fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+}
+
+/// Hash the q-grams of a token sequence into a binary fingerprint of `nbits` bits
+///
+/// Each bit is stored as a `u8` holding 0 or 1, so the result can be compared with any
+/// metric in `similarity_metrics::measures`. The hash is stable across runs and platforms.
+/// Panics if `q` or `nbits` is 0.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::measures::tanimoto;
+/// use similarity_metrics::qgram::fingerprint;
+/// use similarity_metrics::tokenize::smiles;
+/// let fp1 = fingerprint(&smiles("CC(=O)Oc1ccccc1C(=O)O"), 3, 1024);
+/// let fp2 = fingerprint(&smiles("CC(=O)Nc1ccc(O)cc1"), 3, 1024);
+/// assert_eq!(fp1.len(), 1024);
+/// assert!(fp1.iter().all(|&bit| bit <= 1));
+/// assert_eq!(tanimoto(&fp1, &fp1), 1.0);
+/// assert!(tanimoto(&fp1, &fp2) < 1.0);
+/// ```
+///
+/// ```should_panic
+/// use similarity_metrics::qgram::fingerprint;
+/// fingerprint(&["C", "O"], 2, 0);
+/// ```
+pub fn fingerprint(tokens: &[&str], q: usize, nbits: usize) -> Vec<u8> {
+    assert!(
+        q > 0 && nbits > 0,
+        "q-gram fingerprints need q > 0 and nbits > 0"
+    );
+    profile(tokens, q)
+        .keys()
+        .fold(vec![0; nbits], |mut acc, gram| {
+            acc[(fnv1a(gram) % nbits as u64) as usize] = 1;
+            acc
+        })
+}
+
+/// Number of distinct q-grams in `p1`, in `p2`, and in both
+fn abc(p1: &Profile, p2: &Profile) -> (usize, usize, usize) {
+    (
+        p1.len(),
+        p2.len(),
+        p1.keys().filter(|gram| p2.contains_key(*gram)).count(),
+    )
+}
+
+/// Tanimoto (Jaccard) coefficient between the sets of distinct q-grams, in [0, 1]
+///
+/// Returns 0 if both profiles are empty.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::qgram::{profile, tanimoto};
+/// use similarity_metrics::tokenize::chars;
+/// let (p1, p2) = (profile(&chars("CCCO"), 2), profile(&chars("CCO"), 2));
+/// assert_eq!(tanimoto(&p1, &p2), 1.0);
+/// let (p1, p2) = (profile(&chars("CCO"), 2), profile(&chars("CCN"), 2));
+/// assert_eq!(tanimoto(&p1, &p2), 1.0 / 3.0);
+/// assert_eq!(tanimoto(&profile(&[], 2), &profile(&[], 2)), 0.0);
+/// ```
+pub fn tanimoto(p1: &Profile, p2: &Profile) -> f64 {
+    match abc(p1, p2) {
+        (0, 0, _) => 0.0,
+        (a, b, c) => c as f64 / (a + b - c) as f64,
+    }
+}
+
+/// Dice coefficient between the sets of distinct q-grams, in [0, 1]
+///
+/// Returns 0 if both profiles are empty.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::qgram::{dice, profile};
+/// use similarity_metrics::tokenize::chars;
+/// let (p1, p2) = (profile(&chars("CCO"), 2), profile(&chars("CCN"), 2));
+/// assert_eq!(dice(&p1, &p2), 0.5);
+/// assert_eq!(dice(&p1, &p1), 1.0);
+/// ```
+pub fn dice(p1: &Profile, p2: &Profile) -> f64 {
+    match abc(p1, p2) {
+        (0, 0, _) => 0.0,
+        (a, b, c) => (2 * c) as f64 / (a + b) as f64,
+    }
+}
+
+/// Cosine similarity between q-gram count vectors, in [0, 1]
+///
+/// Unlike `tanimoto` and `dice`, repeated q-grams are weighted by how often they occur.
+/// Returns 0 if either profile is empty.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::qgram::{cosine, profile};
+/// use similarity_metrics::tokenize::chars;
+/// let (p1, p2) = (profile(&chars("CCCO"), 2), profile(&chars("CCO"), 2));
+/// assert!((cosine(&p1, &p2) - 3.0 / 10f64.sqrt()).abs() < 1e-12);
+/// assert!((cosine(&p1, &p1) - 1.0).abs() < 1e-12);
+/// assert_eq!(cosine(&p1, &profile(&chars("NN"), 2)), 0.0);
+/// ```
+pub fn cosine(p1: &Profile, p2: &Profile) -> f64 {
+    let norm = |p: &Profile| p.values().map(|&n| (n * n) as f64).sum::<f64>().sqrt();
+    let dot = p1
+        .iter()
+        .filter_map(|(gram, &n)| p2.get(gram).map(|&m| (n * m) as f64))
+        .sum::<f64>();
+
+    match (norm(p1), norm(p2)) {
+        (n1, n2) if n1 == 0.0 || n2 == 0.0 => 0.0,
+        (n1, n2) => dot / (n1 * n2),
+    }
+}
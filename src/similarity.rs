@@ -0,0 +1,310 @@
+use crate::dist::{
+    _jaro, _jaro_winkler, _l_distance, _l_edits, _lcs, _monge_elkan, _ratcliff_obershelp,
+    _smith_waterman_gotoh, sorensen_dice_tokens, EditOp, Scoring, JARO_WINKLER,
+};
+use crate::tokenize::{self, Tokenizer};
+
+/// How a raw edit or match count is scaled into [0, 1]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Normalisation {
+    /// Divide by the length of the longer sequence
+    #[default]
+    MaxLength,
+    /// Divide by the combined length of both sequences
+    SumLength,
+    /// Divide by the number of columns in the optimal alignment
+    AlignmentLength,
+}
+
+/// A string similarity normalised to [0, 1]
+///
+/// Strings are split into tokens first, and every implementation scores identical
+/// token sequences (including two empty ones) as 1, so different measures can be mixed
+/// and thresholded the same way.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::similarity::{Jaro, Lcs, Levenshtein, StringMetric};
+/// let metrics: Vec<Box<dyn StringMetric>> = vec![
+///     Box::new(Levenshtein::default()),
+///     Box::new(Lcs::default()),
+///     Box::new(Jaro::default()),
+/// ];
+/// for metric in &metrics {
+///     assert_eq!(metric.similarity("CCO", "CCO"), 1.0);
+///     assert_eq!(metric.similarity("", ""), 1.0);
+///     assert_eq!(metric.distance("CCO", "CCO"), 0.0);
+///     let s = metric.similarity("CCO", "CCN");
+///     assert!(0.0 < s && s < 1.0);
+/// }
+/// ```
+pub trait StringMetric {
+    /// Split a string into the tokens that are compared, single characters by default
+    fn tokenize<'a>(&self, s: &'a str) -> Vec<&'a str> {
+        tokenize::chars(s)
+    }
+
+    /// Similarity between two token sequences, before clamping to [0, 1]
+    fn score(&self, a: &[&str], b: &[&str]) -> f64;
+
+    /// Similarity in [0, 1], where 1 means `a` and `b` have identical tokens
+    fn similarity(&self, a: &str, b: &str) -> f64 {
+        let (a, b) = (self.tokenize(a), self.tokenize(b));
+        if a == b {
+            1.0
+        } else {
+            self.score(&a, &b).clamp(0.0, 1.0)
+        }
+    }
+
+    /// Distance in [0, 1], i.e. `1 - similarity(a, b)`
+    fn distance(&self, a: &str, b: &str) -> f64 {
+        1.0 - self.similarity(a, b)
+    }
+}
+
+/// Normalised Levenshtein similarity, `1 - distance / length`
+///
+/// Two empty token sequences have similarity 1.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::similarity::{Levenshtein, Normalisation, StringMetric};
+/// use similarity_metrics::tokenize::smiles;
+/// assert_eq!(Levenshtein::default().similarity("CCCl", "CCBr"), 0.5);
+/// let tokens = Levenshtein { tokenizer: smiles, ..Default::default() };
+/// assert_eq!(tokens.similarity("CCCl", "CCBr"), 1.0 - 1.0 / 3.0);
+/// let sum = Levenshtein { normalisation: Normalisation::SumLength, ..Default::default() };
+/// assert_eq!(sum.similarity("kitten", "sitting"), 1.0 - 3.0 / 13.0);
+/// let aligned = Levenshtein { normalisation: Normalisation::AlignmentLength, ..Default::default() };
+/// assert_eq!(aligned.similarity("kitten", "sitting"), 1.0 - 3.0 / 7.0);
+/// let words = Levenshtein { tokenizer: |s| s.split_whitespace().collect(), ..Default::default() };
+/// assert_eq!(words.similarity(" ", ""), 1.0);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Levenshtein {
+    pub normalisation: Normalisation,
+    pub tokenizer: Tokenizer,
+}
+
+impl Default for Levenshtein {
+    fn default() -> Self {
+        Levenshtein {
+            normalisation: Normalisation::default(),
+            tokenizer: tokenize::chars,
+        }
+    }
+}
+
+impl StringMetric for Levenshtein {
+    fn tokenize<'a>(&self, s: &'a str) -> Vec<&'a str> {
+        (self.tokenizer)(s)
+    }
+
+    fn score(&self, a: &[&str], b: &[&str]) -> f64 {
+        let (distance, length) = match self.normalisation {
+            Normalisation::MaxLength => (_l_distance(a, b), usize::max(a.len(), b.len())),
+            Normalisation::SumLength => (_l_distance(a, b), a.len() + b.len()),
+            Normalisation::AlignmentLength => {
+                let edits = _l_edits(a, b);
+                let inserted = edits.iter().filter(|e| e.op == EditOp::Insert).count();
+                (edits.len(), a.len() + inserted)
+            }
+        };
+
+        if length == 0 {
+            1.0
+        } else {
+            1.0 - distance as f64 / length as f64
+        }
+    }
+}
+
+/// Normalised longest common subsequence similarity, `lcs / length`
+///
+/// With `SumLength` the similarity is `2 * lcs / (|a| + |b|)`, and with `AlignmentLength`
+/// it is `lcs / (|a| + |b| - lcs)`. Two empty token sequences have similarity 1.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::similarity::{Lcs, Normalisation, StringMetric};
+/// assert_eq!(Lcs::default().similarity("CCCO", "CCO"), 0.75);
+/// let sum = Lcs { normalisation: Normalisation::SumLength, ..Default::default() };
+/// assert_eq!(sum.similarity("CCCO", "CCO"), 6.0 / 7.0);
+/// let aligned = Lcs { normalisation: Normalisation::AlignmentLength, ..Default::default() };
+/// assert_eq!(aligned.similarity("CCO", "CCN"), 0.5);
+/// assert_eq!(aligned.score(&[], &[]), 1.0);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Lcs {
+    pub normalisation: Normalisation,
+    pub tokenizer: Tokenizer,
+}
+
+impl Default for Lcs {
+    fn default() -> Self {
+        Lcs {
+            normalisation: Normalisation::default(),
+            tokenizer: tokenize::chars,
+        }
+    }
+}
+
+impl StringMetric for Lcs {
+    fn tokenize<'a>(&self, s: &'a str) -> Vec<&'a str> {
+        (self.tokenizer)(s)
+    }
+
+    fn score(&self, a: &[&str], b: &[&str]) -> f64 {
+        let common = _lcs(a, b);
+        let (matched, length) = match self.normalisation {
+            Normalisation::MaxLength => (common, usize::max(a.len(), b.len())),
+            Normalisation::SumLength => (2 * common, a.len() + b.len()),
+            Normalisation::AlignmentLength => (common, a.len() + b.len() - common),
+        };
+
+        if length == 0 {
+            1.0
+        } else {
+            matched as f64 / length as f64
+        }
+    }
+}
+
+/// Jaro similarity (see `similarity_metrics::dist::jaro`)
+#[derive(Debug, Clone, Copy)]
+pub struct Jaro {
+    pub tokenizer: Tokenizer,
+}
+
+impl Default for Jaro {
+    fn default() -> Self {
+        Jaro {
+            tokenizer: tokenize::chars,
+        }
+    }
+}
+
+impl StringMetric for Jaro {
+    fn tokenize<'a>(&self, s: &'a str) -> Vec<&'a str> {
+        (self.tokenizer)(s)
+    }
+
+    fn score(&self, a: &[&str], b: &[&str]) -> f64 {
+        _jaro(a, b)
+    }
+}
+
+/// Jaro–Winkler similarity (see `similarity_metrics::dist::jaro_winkler`)
+#[derive(Debug, Clone, Copy)]
+pub struct JaroWinkler {
+    pub p: Option<f64>,
+    pub tokenizer: Tokenizer,
+}
+
+impl Default for JaroWinkler {
+    fn default() -> Self {
+        JaroWinkler {
+            p: None,
+            tokenizer: tokenize::chars,
+        }
+    }
+}
+
+impl StringMetric for JaroWinkler {
+    fn tokenize<'a>(&self, s: &'a str) -> Vec<&'a str> {
+        (self.tokenizer)(s)
+    }
+
+    fn score(&self, a: &[&str], b: &[&str]) -> f64 {
+        _jaro_winkler(a, b, self.p)
+    }
+}
+
+/// Ratcliff-Obershelp similarity (see `similarity_metrics::dist::ratcliff_obershelp`)
+#[derive(Debug, Clone, Copy)]
+pub struct RatcliffObershelp {
+    pub tokenizer: Tokenizer,
+}
+
+impl Default for RatcliffObershelp {
+    fn default() -> Self {
+        RatcliffObershelp {
+            tokenizer: tokenize::chars,
+        }
+    }
+}
+
+impl StringMetric for RatcliffObershelp {
+    fn tokenize<'a>(&self, s: &'a str) -> Vec<&'a str> {
+        (self.tokenizer)(s)
+    }
+
+    fn score(&self, a: &[&str], b: &[&str]) -> f64 {
+        _ratcliff_obershelp(a, b)
+    }
+}
+
+/// Smith-Waterman-Gotoh similarity (see `similarity_metrics::dist::smith_waterman_gotoh`)
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::dist::Scoring;
+/// use similarity_metrics::similarity::{SmithWatermanGotoh, StringMetric};
+/// use similarity_metrics::tokenize::smiles;
+/// let metric = SmithWatermanGotoh { scoring: Scoring::new(2, -1, -2, -1), tokenizer: smiles };
+/// assert_eq!(metric.similarity("CCCl", "OCCCl"), 1.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SmithWatermanGotoh<'s> {
+    pub scoring: Scoring<&'s str>,
+    pub tokenizer: Tokenizer,
+}
+
+impl Default for SmithWatermanGotoh<'_> {
+    fn default() -> Self {
+        SmithWatermanGotoh {
+            scoring: Scoring::default(),
+            tokenizer: tokenize::chars,
+        }
+    }
+}
+
+impl StringMetric for SmithWatermanGotoh<'_> {
+    fn tokenize<'a>(&self, s: &'a str) -> Vec<&'a str> {
+        (self.tokenizer)(s)
+    }
+
+    fn score(&self, a: &[&str], b: &[&str]) -> f64 {
+        _smith_waterman_gotoh(a, b, &self.scoring)
+    }
+}
+
+/// Monge-Elkan similarity over words (see `similarity_metrics::dist::monge_elkan`)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MongeElkan;
+
+impl StringMetric for MongeElkan {
+    fn tokenize<'a>(&self, s: &'a str) -> Vec<&'a str> {
+        s.split_whitespace().collect()
+    }
+
+    fn score(&self, a: &[&str], b: &[&str]) -> f64 {
+        _monge_elkan(a, b, |x, y| JARO_WINKLER(x, y, None))
+    }
+}
+
+/// Sørensen-Dice similarity over character bigrams
+/// (see `similarity_metrics::dist::sorensen_dice`)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SorensenDice;
+
+impl StringMetric for SorensenDice {
+    fn score(&self, a: &[&str], b: &[&str]) -> f64 {
+        sorensen_dice_tokens(a, b)
+    }
+}
@@ -0,0 +1,108 @@
+/// Length in bytes of the bracketed group starting at the beginning of `s`,
+/// or `None` if `s` does not start with a closed `[...]` group
+fn bracket_len(s: &str) -> Option<usize> {
+    if s.starts_with('[') {
+        s.find(']').map(|end| end + 1)
+    } else {
+        None
+    }
+}
+
+/// Length in bytes of the first character of `s`
+fn char_len(s: &str) -> usize {
+    s.chars().next().map_or(0, char::len_utf8)
+}
+
+/// Split a string into tokens, where `next` returns the byte length of the
+/// token at the start of the remaining input
+fn split_with(s: &str, next: fn(&str) -> usize) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut rest = s;
+
+    while !rest.is_empty() {
+        let (token, tail) = rest.split_at(next(rest));
+        tokens.push(token);
+        rest = tail;
+    }
+
+    tokens
+}
+
+/// Byte length of the SMILES token at the start of `s`
+fn next_smiles(s: &str) -> usize {
+    if let Some(len) = bracket_len(s) {
+        return len;
+    }
+
+    let bytes = s.as_bytes();
+    match bytes {
+        [b'B', b'r', ..] | [b'C', b'l', ..] => 2,
+        [b'%', d1, d2, ..] if d1.is_ascii_digit() && d2.is_ascii_digit() => 3,
+        _ => char_len(s),
+    }
+}
+
+/// Byte length of the SELFIES symbol at the start of `s`
+fn next_selfies(s: &str) -> usize {
+    bracket_len(s).unwrap_or_else(|| char_len(s))
+}
+
+/// Split a SMILES string into chemically meaningful tokens
+///
+/// Bracket atoms (e.g. `[C@@H]`, `[nH]`, `[O-]`), the two-letter organic subset
+/// atoms `Br` and `Cl`, and two-digit ring closures (`%10`) are kept whole;
+/// every other character is its own token.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::tokenize::smiles;
+/// assert_eq!(smiles("CCl"), ["C", "Cl"]);
+/// assert_eq!(smiles("C[C@@H](Br)O"), ["C", "[C@@H]", "(", "Br", ")", "O"]);
+/// assert_eq!(smiles("c1ccccc1"), ["c", "1", "c", "c", "c", "c", "c", "1"]);
+/// assert_eq!(smiles("C%12CC%12"), ["C", "%12", "C", "C", "%12"]);
+/// assert_eq!(smiles("[Na+].[Cl-]"), ["[Na+]", ".", "[Cl-]"]);
+/// assert_eq!(smiles(""), [] as [&str; 0]);
+/// ```
+pub fn smiles(s: &str) -> Vec<&str> {
+    split_with(s.trim(), next_smiles)
+}
+
+/// Split a SELFIES string into symbols
+///
+/// Every bracketed symbol (e.g. `[C]`, `[=O]`, `[Branch1]`, `[Ring2]`) is one token.
+/// Anything outside brackets, such as the `.` separating fragments,
+/// is split into single characters.
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::tokenize::selfies;
+/// assert_eq!(selfies("[C][=O]"), ["[C]", "[=O]"]);
+/// assert_eq!(
+///     selfies("[C][Branch1][C][O][C]"),
+///     ["[C]", "[Branch1]", "[C]", "[O]", "[C]"]
+/// );
+/// assert_eq!(selfies("[Na+].[Cl-]"), ["[Na+]", ".", "[Cl-]"]);
+/// assert_eq!(selfies(""), [] as [&str; 0]);
+/// ```
+pub fn selfies(s: &str) -> Vec<&str> {
+    split_with(s.trim(), next_selfies)
+}
+
+/// Split a string into single-character tokens
+///
+/// ## Examples
+///
+/// ```
+/// use similarity_metrics::tokenize::chars;
+/// assert_eq!(chars("CCl"), ["C", "C", "l"]);
+/// assert_eq!(chars("Å[O]"), ["Å", "[", "O", "]"]);
+/// assert_eq!(chars(""), [] as [&str; 0]);
+/// ```
+pub fn chars(s: &str) -> Vec<&str> {
+    split_with(s, char_len)
+}
+
+/// Function splitting a string into tokens, such as `chars`, `smiles` or `selfies`
+pub type Tokenizer = fn(&str) -> Vec<&str>;
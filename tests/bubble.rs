@@ -0,0 +1,295 @@
+mod common;
+
+use common::{block_groups, mixed_density_fps};
+use rand::{Rng, SeedableRng};
+use similarity_metrics::cluster::{
+    cluster, cluster_by, compute_bubbles, compute_bubbles_by, euclidean_bound, optics, seeded_rng,
+    soergel_bound, Bubble, BubbleOptics, SeedIndex,
+};
+use similarity_metrics::measures::{euclidean, soergel};
+
+fn brute_force_extent(objects: &[Vec<u8>], metric: fn(&[u8], &[u8]) -> f64) -> f64 {
+    let n = objects.len();
+    let mut total = 0.0;
+    for i in 0..n {
+        for j in 0..n {
+            if i != j {
+                total += metric(&objects[i], &objects[j]).powi(2);
+            }
+        }
+    }
+    (total / (n * (n - 1)) as f64).sqrt()
+}
+
+#[test]
+fn test_extent() {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+
+    for n in 2..20 {
+        let objects: Vec<Vec<u8>> = (0..n)
+            .map(|_| (0..64).map(|_| rng.gen_range(0..=1)).collect())
+            .collect();
+        let bubble = Bubble::new(objects.clone());
+
+        for metric in [euclidean, soergel] {
+            let expected = brute_force_extent(&objects, metric);
+            assert!((bubble.extent(metric) - expected).abs() < 1e-9);
+        }
+    }
+}
+
+#[test]
+fn test_extent_small() {
+    assert_eq!(Bubble::new(vec![vec![0, 1, 1, 0]]).extent(euclidean), 0.0);
+    assert_eq!(
+        Bubble::new(vec![vec![0, 1], vec![0, 1]]).extent(euclidean),
+        0.0
+    );
+    assert_eq!(
+        Bubble::new(vec![vec![0, 1], vec![1, 0]]).extent(euclidean),
+        2f64.sqrt()
+    );
+    assert_eq!(
+        Bubble::new(vec![vec![1, 1, 0], vec![1, 0, 0], vec![0, 0, 1]]).extent(euclidean),
+        (2.0 * (1.0 + 3.0 + 2.0) / 6.0f64).sqrt()
+    );
+}
+
+#[test]
+fn test_nn_dist() {
+    let bubble = Bubble::new(vec![vec![1, 1, 0, 0], vec![1, 0, 0, 0], vec![0, 0, 1, 1]]);
+    let extent = bubble.extent(euclidean);
+    assert_eq!(bubble.nn_dist(3, euclidean), extent);
+    assert!((bubble.nn_dist(1, euclidean) - (1.0 / 3f64).powf(0.25) * extent).abs() < 1e-12);
+}
+
+/// Two groups of fingerprints with no bits in common
+fn two_groups() -> Vec<Vec<u8>> {
+    block_groups(&[20, 20], 32, 24, 0.3, 1)
+}
+
+#[test]
+fn test_optics_separates_groups() {
+    let fps = two_groups();
+    let result = cluster_by(fps.clone(), fps.len(), 4.0, 3, euclidean);
+
+    assert_eq!(result.clusters.len(), 2);
+    let mut clusters = result.clusters.clone();
+    clusters.sort();
+    assert_eq!(clusters[0], (0..20).collect::<Vec<_>>());
+    assert_eq!(clusters[1], (20..40).collect::<Vec<_>>());
+    assert!(result.noise.is_empty());
+    assert!(result.labels.iter().all(Option::is_some));
+    assert_eq!(result.sizes(), [20, 20]);
+
+    assert_eq!(result.bubbles.map(|b| b.len()), Some(fps.len()));
+
+    let plot = result.plot.unwrap();
+    let mut order = plot.order.clone();
+    order.sort_unstable();
+    assert_eq!(order, (0..fps.len()).collect::<Vec<_>>());
+    assert_eq!(plot.reachability.iter().filter(|r| **r > 4.0).count(), 2);
+}
+
+#[test]
+fn test_fit_virtual_reachability() {
+    // Every object after the first in its bubble is reached at the expected
+    // distance to its `min_pts`-th nearest neighbour within the bubble
+    let fps = two_groups();
+    let result = BubbleOptics {
+        metric: euclidean,
+        ..BubbleOptics::new(4, 10.0, 3)
+    }
+    .fit(&fps);
+    let bubble_of = result.bubbles.unwrap();
+    let plot = result.plot.unwrap();
+
+    let mut inner = 0;
+    for (position, pair) in plot.order.windows(2).enumerate() {
+        let b = bubble_of[pair[1]];
+        if bubble_of[pair[0]] == b {
+            let members = (0..fps.len()).filter(|&i| bubble_of[i] == b).collect();
+            let expected = Bubble::of(&fps, members).nn_dist(3, euclidean);
+            assert_eq!(plot.reachability[position + 1], expected);
+            inner += 1;
+        }
+    }
+    let n_bubbles = bubble_of.iter().max().unwrap() + 1;
+    assert_eq!(inner, fps.len() - n_bubbles);
+}
+
+#[test]
+fn test_optics_weights_bubbles() {
+    // A single bubble holding enough objects is a core bubble on its own
+    let bubbles = vec![
+        Bubble::new(vec![vec![1, 1, 0, 0]; 5]),
+        Bubble::new(vec![vec![0, 0, 1, 1]]),
+    ];
+    let plot = optics(&bubbles, f64::MAX, 5, euclidean);
+    assert_eq!(plot.order, [0, 1]);
+    assert_eq!(plot.core_distance[0], 0.0);
+    assert_eq!(plot.reachability, [f64::INFINITY, 2.0]);
+    assert_eq!(plot.core_distance[1], 2.0);
+
+    let plot = optics(&bubbles, 1.0, 6, euclidean);
+    assert!(plot.core_distance.iter().all(|d| d.is_infinite()));
+    assert!(plot.reachability.iter().all(|d| d.is_infinite()));
+}
+
+#[test]
+fn test_medoid() {
+    let bubble = Bubble::new(vec![
+        vec![1, 1, 1, 0],
+        vec![1, 1, 0, 0],
+        vec![1, 0, 0, 0],
+        vec![0, 0, 0, 1],
+    ]);
+    assert_eq!(bubble.medoid(euclidean), [1, 1, 0, 0]);
+    assert_eq!(bubble.medoid(soergel), [1, 1, 0, 0]);
+    assert_eq!(Bubble::new(vec![vec![0, 1]]).medoid(soergel), [0, 1]);
+}
+
+#[test]
+fn test_soergel_default() {
+    assert_eq!(soergel(&[0, 0, 0], &[0, 0, 0]), 0.0);
+
+    let fps = two_groups();
+    let result = cluster(fps.clone(), fps.len(), 0.5, 3);
+    assert_eq!(result.sizes(), [20, 20]);
+
+    let bubbles = compute_bubbles_by(&fps, 5, soergel, SeedIndex::Exhaustive, &mut seeded_rng(3));
+    assert_eq!(bubbles.iter().map(Bubble::n).sum::<usize>(), fps.len());
+}
+
+#[test]
+fn test_seeded_runs_agree() {
+    let fps = two_groups();
+    let optics = BubbleOptics {
+        seed: 42,
+        ..BubbleOptics::new(8, 0.6, 3)
+    };
+    assert_eq!(optics.fit(&fps), optics.fit(&fps));
+    assert_eq!(
+        cluster(fps.clone(), 8, 0.6, 3),
+        cluster(fps.clone(), 8, 0.6, 3)
+    );
+
+    let sizes = |seed| {
+        compute_bubbles_by(
+            &fps,
+            8,
+            soergel,
+            SeedIndex::Exhaustive,
+            &mut seeded_rng(seed),
+        )
+        .iter()
+        .map(Bubble::n)
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(sizes(1), sizes(1));
+}
+
+#[test]
+fn test_noise_and_labels() {
+    // Two tight groups and one fingerprint far from both
+    let mut fps = vec![vec![1, 1, 1, 1, 0, 0, 0, 0]; 4];
+    fps.extend(vec![vec![0, 0, 0, 0, 1, 1, 1, 1]; 4]);
+    fps.insert(3, vec![1, 0, 0, 0, 0, 0, 0, 1]);
+
+    let result = cluster(fps.clone(), fps.len(), 0.5, 3);
+    assert_eq!(result.n_clusters(), 2);
+    assert_eq!(result.noise, [3]);
+    assert_eq!(result.labels[3], None);
+    for (c, members) in result.clusters.iter().enumerate() {
+        assert!(members.iter().all(|&i| result.labels[i] == Some(c)));
+    }
+
+    let bubbles = result.bubbles.unwrap();
+    assert_eq!(bubbles.len(), fps.len());
+    assert_eq!(bubbles[0], bubbles[1]);
+    assert_ne!(bubbles[3], bubbles[0]);
+}
+
+#[test]
+fn test_seed_indexes_agree() {
+    let fps = mixed_density_fps(300, 64, 0.05..0.6, 2);
+
+    let members = |metric, index| {
+        compute_bubbles_by(&fps, 40, metric, index, &mut seeded_rng(5))
+            .iter()
+            .map(|b| b.members().to_vec())
+            .collect::<Vec<_>>()
+    };
+    for (metric, bound) in [
+        (
+            soergel as fn(&[u8], &[u8]) -> f64,
+            soergel_bound as fn(usize, usize) -> f64,
+        ),
+        (euclidean, euclidean_bound),
+    ] {
+        let exhaustive = members(metric, SeedIndex::Exhaustive);
+        assert_eq!(members(metric, SeedIndex::VpTree), exhaustive);
+        assert_eq!(members(metric, SeedIndex::Popcount(bound)), exhaustive);
+
+        let mut all = exhaustive.concat();
+        all.sort_unstable();
+        assert_eq!(all, (0..fps.len()).collect::<Vec<_>>());
+    }
+
+    let bubbles = compute_bubbles(&fps, 10);
+    let bubble = bubbles.iter().find(|b| b.n() > 1).unwrap();
+    let objects = bubble
+        .members()
+        .iter()
+        .map(|&i| fps[i].clone())
+        .collect::<Vec<_>>();
+    assert_eq!(bubble.extent(soergel), Bubble::new(objects).extent(soergel));
+
+    let optics = BubbleOptics {
+        index: SeedIndex::Popcount(soergel_bound),
+        ..BubbleOptics::new(40, 0.5, 3)
+    };
+    assert_eq!(optics.fit(&fps), BubbleOptics::new(40, 0.5, 3).fit(&fps));
+}
+
+#[test]
+fn test_more_bubbles_than_objects() {
+    // Every object is a seed, and the identical pair shares one bubble
+    let fps = vec![vec![1, 1, 0, 0], vec![1, 1, 0, 0], vec![0, 0, 1, 1]];
+    for index in [
+        SeedIndex::Exhaustive,
+        SeedIndex::VpTree,
+        SeedIndex::Popcount(euclidean_bound),
+    ] {
+        let bubbles = compute_bubbles_by(&fps, 10, euclidean, index, &mut seeded_rng(0));
+        let mut members = bubbles
+            .iter()
+            .map(|b| b.members().to_vec())
+            .collect::<Vec<_>>();
+        members.sort();
+        assert_eq!(members, [vec![0, 1], vec![2]]);
+        for bubble in &bubbles {
+            assert_eq!(bubble.d(), 4);
+            assert_eq!(bubble.rep().len(), 4);
+            assert!(bubble.nn_dist(1, euclidean).is_finite());
+        }
+    }
+
+    let result = cluster(fps.clone(), 10, 0.5, 2);
+    assert_eq!(result.bubbles.map(|b| b.len()), Some(fps.len()));
+}
+
+#[test]
+fn test_no_bubbles() {
+    for index in [SeedIndex::Exhaustive, SeedIndex::VpTree] {
+        assert!(compute_bubbles_by(&[], 0, euclidean, index, &mut seeded_rng(0)).is_empty());
+        assert!(compute_bubbles_by(&[], 3, euclidean, index, &mut seeded_rng(0)).is_empty());
+    }
+    assert!(cluster(vec![], 0, 0.5, 2).labels.is_empty());
+}
+
+#[test]
+#[should_panic(expected = "at least one bubble")]
+fn test_zero_bubbles() {
+    compute_bubbles(&[vec![1, 0]], 0);
+}
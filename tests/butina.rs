@@ -0,0 +1,141 @@
+mod common;
+
+use common::mixed_density_fps;
+use similarity_metrics::cluster::{butina, euclidean_bound, Butina, CentroidClustering};
+use similarity_metrics::measures::{euclidean, soergel};
+
+/// Butina clustering from the full distance matrix
+fn brute_force(
+    fps: &[Vec<u8>],
+    threshold: f64,
+    metric: fn(&[u8], &[u8]) -> f64,
+) -> Vec<Vec<usize>> {
+    let n = fps.len();
+    let neighbours = (0..n)
+        .map(|i| {
+            (0..n)
+                .filter(|&j| j != i && metric(&fps[i], &fps[j]) <= threshold)
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut order = (0..n).collect::<Vec<_>>();
+    order.sort_by(|&a, &b| {
+        neighbours[b]
+            .len()
+            .cmp(&neighbours[a].len())
+            .then(a.cmp(&b))
+    });
+
+    let mut assigned = vec![false; n];
+    let mut clusters = Vec::new();
+    for i in order {
+        if !assigned[i] {
+            assigned[i] = true;
+            let mut members = vec![i];
+            for &j in &neighbours[i] {
+                if !assigned[j] {
+                    assigned[j] = true;
+                    members.push(j);
+                }
+            }
+            clusters.push(members);
+        }
+    }
+    clusters
+}
+
+fn all_clusters(result: &CentroidClustering) -> Vec<Vec<usize>> {
+    let mut clusters = result
+        .clusters
+        .iter()
+        .map(|c| c.members.clone())
+        .chain(result.singletons.iter().map(|&s| vec![s]))
+        .collect::<Vec<_>>();
+    clusters.sort();
+    clusters
+}
+
+#[test]
+fn test_butina_matches_brute_force() {
+    for seed in 0..5 {
+        let fps = mixed_density_fps(200, 32, 0.1..0.5, seed);
+
+        for threshold in [0.2, 0.4, 0.6] {
+            let mut expected = brute_force(&fps, threshold, soergel);
+            expected.sort();
+            assert_eq!(all_clusters(&butina(&fps, threshold, soergel)), expected);
+            assert_eq!(
+                all_clusters(&Butina::tanimoto(threshold).fit(&fps)),
+                expected
+            );
+        }
+
+        let mut expected = brute_force(&fps, 2.5, euclidean);
+        expected.sort();
+        let pruned = Butina {
+            bound: Some(euclidean_bound),
+            ..Butina::new(2.5, euclidean)
+        };
+        assert_eq!(all_clusters(&pruned.fit(&fps)), expected);
+    }
+}
+
+#[test]
+fn test_butina_structure() {
+    let fps = mixed_density_fps(300, 32, 0.1..0.5, 7);
+    let result = Butina::tanimoto(0.5).fit(&fps);
+
+    for cluster in &result.clusters {
+        assert!(cluster.members.len() > 1);
+        assert_eq!(cluster.members[0], cluster.centroid);
+        assert!(cluster
+            .members
+            .iter()
+            .all(|&i| soergel(&fps[i], &fps[cluster.centroid]) <= 0.5));
+    }
+    let mut seen = all_clusters(&result).concat();
+    seen.sort_unstable();
+    assert_eq!(seen, (0..fps.len()).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_butina_ties_and_singletons() {
+    // Two identical pairs: the lower index wins the tie for centroid
+    let fps = vec![
+        vec![1, 1, 0, 0],
+        vec![0, 0, 1, 1],
+        vec![1, 1, 0, 0],
+        vec![0, 0, 1, 1],
+    ];
+    let result = butina(&fps, 0.0, soergel);
+    assert_eq!(result.clusters[0].members, [0, 2]);
+    assert_eq!(result.clusters[1].members, [1, 3]);
+    assert!(result.singletons.is_empty());
+
+    assert_eq!(butina(&[], 0.5, soergel).clusters, []);
+}
+
+#[test]
+fn test_reassign_singletons() {
+    // 1 and 2 are neighbours of 0; 3 neighbours only 2, which 0 has already taken
+    let fps = vec![
+        vec![1, 1, 1, 1, 0, 0],
+        vec![1, 1, 1, 0, 0, 0],
+        vec![1, 1, 1, 1, 1, 0],
+        vec![0, 1, 1, 1, 1, 1],
+        vec![0, 0, 0, 0, 0, 1],
+    ];
+    let butina = Butina::tanimoto(0.35);
+    let result = butina.fit(&fps);
+    assert_eq!(result.clusters[0].members, [0, 1, 2]);
+    assert_eq!(result.singletons, [3, 4]);
+
+    let result = Butina {
+        reassign_singletons: true,
+        ..butina
+    }
+    .fit(&fps);
+    assert_eq!(result.clusters[0].members, [0, 1, 2, 3]);
+    assert_eq!(result.singletons, [4]);
+}
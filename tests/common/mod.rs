@@ -0,0 +1,49 @@
+// Each test crate includes this module but uses only some of its fixtures
+#![allow(dead_code)]
+
+use rand::{Rng, SeedableRng};
+use std::ops::Range;
+
+/// `n` random fingerprints of `bits` bits, each bit set with probability 0.3
+pub fn random_fps(n: usize, bits: usize, seed: u64) -> Vec<Vec<u8>> {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    (0..n)
+        .map(|_| (0..bits).map(|_| rng.gen_bool(0.3) as u8).collect())
+        .collect()
+}
+
+/// `n` random fingerprints of `bits` bits, each with its own bit density drawn
+/// from `densities`, so that popcounts vary widely
+pub fn mixed_density_fps(n: usize, bits: usize, densities: Range<f64>, seed: u64) -> Vec<Vec<u8>> {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    (0..n)
+        .map(|_| {
+            let density = rng.gen_range(densities.clone());
+            (0..bits).map(|_| rng.gen_bool(density) as u8).collect()
+        })
+        .collect()
+}
+
+/// Groups of `sizes[g]` fingerprints, one group after another, each group with its own
+/// block of `block_bits` bits and no bits outside it; the first `core_bits` of the block
+/// are always set and the rest with probability `p`
+pub fn block_groups(
+    sizes: &[usize],
+    block_bits: usize,
+    core_bits: usize,
+    p: f64,
+    seed: u64,
+) -> Vec<Vec<u8>> {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    let mut fps = Vec::with_capacity(sizes.iter().sum());
+    for (g, &size) in sizes.iter().enumerate() {
+        for _ in 0..size {
+            let mut fp = vec![0; block_bits * sizes.len()];
+            for bit in 0..block_bits {
+                fp[block_bits * g + bit] = (bit < core_bits || rng.gen_bool(p)) as u8;
+            }
+            fps.push(fp);
+        }
+    }
+    fps
+}
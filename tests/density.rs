@@ -0,0 +1,97 @@
+mod common;
+
+use common::{block_groups, random_fps};
+use similarity_metrics::cluster::{dbscan, hdbscan, BubbleOptics, ReachabilityPlot};
+use similarity_metrics::measures::soergel;
+
+/// Three groups of fingerprints with no bits in common, plus a few random ones
+fn groups() -> Vec<Vec<u8>> {
+    let mut fps = block_groups(&[20, 20, 20], 32, 20, 0.25, 7);
+    fps.extend(random_fps(4, 96, 8));
+    fps
+}
+
+#[test]
+fn test_dbscan_matches_brute_force() {
+    let fps = groups();
+    let (eps, min_samples) = (0.305, 4);
+    let result = dbscan(&fps, eps, min_samples, soergel);
+
+    let neighbours = |i: usize| {
+        (0..fps.len())
+            .filter(|&j| soergel(&fps[i], &fps[j]) <= eps)
+            .collect::<Vec<_>>()
+    };
+    for i in 0..fps.len() {
+        let near = neighbours(i);
+        if near.len() >= min_samples {
+            // Core objects share a cluster with every neighbour
+            let label = result.labels[i];
+            assert!(label.is_some());
+            assert!(near.iter().all(|&j| result.labels[j] == label));
+        } else if near.iter().all(|&j| neighbours(j).len() < min_samples) {
+            assert_eq!(result.labels[i], None);
+        }
+    }
+
+    assert_eq!(result.n_clusters(), 3);
+    for (c, members) in result.clusters.iter().enumerate() {
+        assert!(members.iter().all(|&i| i >= 60 || i / 20 == c));
+    }
+}
+
+#[test]
+fn test_hdbscan_groups_and_scores() {
+    let fps = groups();
+    let (result, scores) = hdbscan(&fps, 4, 5, soergel);
+
+    assert_eq!(result.n_clusters(), 3);
+    assert_eq!(scores.len(), fps.len());
+    for (c, members) in result.clusters.iter().enumerate() {
+        assert!(members.iter().all(|&i| i >= 60 || i / 20 == c));
+        assert!(members.len() >= 20);
+    }
+    assert_eq!(dbscan(&[], 0.3, 2, soergel).labels, []);
+    assert_eq!(hdbscan(&[], 2, 2, soergel).0.labels, []);
+}
+
+#[test]
+fn test_xi_nested_and_noise() {
+    let inf = f64::INFINITY;
+    // A wide valley containing two narrow ones; the steep rise after the second
+    // belongs to it, while the first item, reached from nowhere, is noise
+    let plot = ReachabilityPlot {
+        order: vec![5, 4, 3, 2, 1, 0, 6, 7, 8, 9],
+        reachability: vec![inf, 0.5, 0.1, 0.1, 0.1, 0.4, 0.1, 0.1, 0.1, 2.0],
+        core_distance: vec![0.1; 10],
+    };
+    let (clusters, noise) = plot.extract_xi(0.3, 2);
+    assert_eq!(clusters, [vec![4, 3, 2, 1], vec![0, 6, 7, 8, 9]]);
+    assert_eq!(noise, [5]);
+}
+
+#[test]
+fn test_xi_on_optics() {
+    let fps = groups();
+    let plot = BubbleOptics::new(fps.len(), 1.0, 4).fit(&fps).plot.unwrap();
+    let (clusters, noise) = plot.extract_xi(0.1, 4);
+
+    assert_eq!(clusters.len(), 3);
+    let mut covered = clusters.concat();
+    covered.extend(&noise);
+    covered.sort_unstable();
+    assert_eq!(covered, (0..fps.len()).collect::<Vec<_>>());
+    for cluster in &clusters {
+        let group = cluster[cluster.len() / 2] / 20;
+        assert!(cluster
+            .iter()
+            .filter(|&&i| i < 60)
+            .all(|&i| i / 20 == group));
+    }
+}
+
+#[test]
+#[should_panic(expected = "same length")]
+fn test_ragged_fingerprints() {
+    dbscan(&[vec![1, 0, 1], vec![1, 0]], 0.5, 1, soergel);
+}
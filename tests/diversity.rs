@@ -0,0 +1,82 @@
+mod common;
+
+use common::random_fps;
+use similarity_metrics::diversity::{maxmin_extend, maxmin_pick, sphere_exclusion_pick};
+use similarity_metrics::measures::{euclidean, soergel};
+
+/// MaxMin recomputing every distance to every pick, the earliest on ties
+fn brute_force_maxmin(
+    fps: &[Vec<u8>],
+    mut picks: Vec<usize>,
+    n: usize,
+    metric: fn(&[u8], &[u8]) -> f64,
+) -> Vec<usize> {
+    while picks.len() < n.min(fps.len()) {
+        let mut best = (f64::NEG_INFINITY, 0);
+        for i in (0..fps.len()).filter(|i| !picks.contains(i)) {
+            let nearest = picks
+                .iter()
+                .map(|&p| metric(&fps[i], &fps[p]))
+                .fold(f64::INFINITY, f64::min);
+            if nearest > best.0 {
+                best = (nearest, i);
+            }
+        }
+        picks.push(best.1);
+    }
+    picks
+}
+
+#[test]
+fn test_maxmin_matches_brute_force() {
+    let fps = random_fps(200, 64, 3);
+    for metric in [soergel, euclidean] {
+        let picks = maxmin_pick(&fps, 25, 9, metric);
+        assert_eq!(picks.len(), 25);
+        assert_eq!(picks, brute_force_maxmin(&fps, vec![picks[0]], 25, metric));
+        assert_eq!(picks, maxmin_pick(&fps, 25, 9, metric));
+    }
+
+    assert_eq!(maxmin_pick(&fps, 0, 0, soergel), []);
+    assert_eq!(maxmin_pick(&[], 5, 0, soergel), []);
+    let mut all = maxmin_pick(&fps[..10], 20, 0, soergel);
+    all.sort_unstable();
+    assert_eq!(all, (0..10).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_maxmin_extend() {
+    let fps = random_fps(150, 64, 4);
+    let existing = vec![5, 17, 42];
+    let picks = maxmin_extend(&fps, &existing, 12, 0, soergel);
+    assert_eq!(picks[..3], existing);
+    assert_eq!(
+        picks,
+        brute_force_maxmin(&fps, existing.clone(), 12, soergel)
+    );
+
+    // Extending a selection continues it exactly
+    let first = maxmin_pick(&fps, 6, 1, soergel);
+    assert_eq!(
+        maxmin_extend(&fps, &first, 15, 99, soergel),
+        maxmin_pick(&fps, 15, 1, soergel)
+    );
+    assert_eq!(maxmin_extend(&fps, &existing, 2, 0, soergel), existing);
+}
+
+#[test]
+fn test_sphere_exclusion() {
+    let fps = random_fps(300, 64, 5);
+    let radius = 0.7;
+    let picks = sphere_exclusion_pick(&fps, radius, soergel);
+
+    assert_eq!(picks[0], 0);
+    for (a, &i) in picks.iter().enumerate() {
+        assert!(picks[..a]
+            .iter()
+            .all(|&j| soergel(&fps[i], &fps[j]) > radius));
+    }
+    for (i, fp) in fps.iter().enumerate() {
+        assert!(picks.contains(&i) || picks.iter().any(|&p| soergel(fp, &fps[p]) <= radius));
+    }
+}
@@ -3,20 +3,37 @@ use similarity_metrics::{cluster, load};
 
 #[test]
 fn test_cluster() {
-    // println!("Generating fingerprints...");
-    // let fps = load::gen_fps(Kind::FP2 { nbits: 2048 }, "tests/large.mol");
-    // println!("Saving fingerprints...");
-    // let json = serde_json::to_string(&fps).unwrap();
-    // std::fs::write("tests/fps.json", json).unwrap();
+    let kinds = vec![
+        (Kind::FP2 { nbits: 512 }, 512),
+        (Kind::FP2 { nbits: 1024 }, 1024),
+        (Kind::FP2 { nbits: 2048 }, 2048),
+        (Kind::ECFP2 { nbits: 1024 }, 1024),
+        (Kind::ECFP4 { nbits: 2048 }, 2048),
+    ];
 
-    println!("Loading fingerprints...");
-    let fps = serde_json::from_str::<Vec<Vec<u8>>>(&std::fs::read_to_string("tests/fps.json").unwrap()).unwrap();
+    for (kind, nbits) in kinds {
+        println!("Generating {:?} fingerprints...", kind);
+        let fps = load::gen_fps(kind, "tests/test.mol");
+        assert!(fps.iter().all(|fp| fp.len() == nbits));
+        assert!(fps.iter().flatten().all(|&bit| bit <= 1));
 
-    println!("Clustering...");
-    let clusters = cluster::cluster(fps, 10000, f64::MAX, 5);
+        println!("Clustering...");
+        let result = cluster::cluster(fps.clone(), 50, f64::MAX, 5);
 
-    // Output to clusters.json
-    println!("Saving clusters...");
-    let json = serde_json::to_string(&clusters).unwrap();
-    std::fs::write("tests/clusters.json", json).unwrap();
+        assert!(result.n_clusters() > 0);
+        assert!(result.clusters.iter().all(|c| !c.is_empty()));
+        assert_eq!(
+            result.sizes().iter().sum::<usize>() + result.noise.len(),
+            fps.len()
+        );
+        assert_eq!(result.labels.len(), fps.len());
+        assert_eq!(result.bubbles.as_ref().map(Vec::len), Some(fps.len()));
+
+        let plot = result.plot.unwrap();
+        let mut order = plot.order.clone();
+        order.sort_unstable();
+        assert_eq!(order, (0..fps.len()).collect::<Vec<_>>());
+        assert_eq!(plot.reachability.len(), fps.len());
+        assert_eq!(plot.core_distance.len(), fps.len());
+    }
 }
@@ -0,0 +1,159 @@
+mod common;
+
+use common::random_fps;
+use ndarray::Array2;
+use rand::{Rng, SeedableRng};
+use similarity_metrics::cluster::{distance_matrix, hierarchical, Linkage};
+use similarity_metrics::measures::soergel;
+
+fn random_points(n: usize, seed: u64) -> Vec<[f64; 3]> {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    (0..n).map(|_| [rng.gen(), rng.gen(), rng.gen()]).collect()
+}
+
+fn euclidean(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (x - y).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
+
+fn centroid(points: &[[f64; 3]], members: &[usize]) -> [f64; 3] {
+    let mut c = [0.0; 3];
+    for &i in members {
+        for (ci, x) in c.iter_mut().zip(points[i]) {
+            *ci += x / members.len() as f64;
+        }
+    }
+    c
+}
+
+/// Linkage distance between two clusters, from its definition
+fn linkage_distance(points: &[[f64; 3]], a: &[usize], b: &[usize], linkage: Linkage) -> f64 {
+    let pairs = a
+        .iter()
+        .flat_map(|&i| b.iter().map(move |&j| euclidean(&points[i], &points[j])));
+    match linkage {
+        Linkage::Single => pairs.fold(f64::INFINITY, f64::min),
+        Linkage::Complete => pairs.fold(0.0, f64::max),
+        Linkage::Average => pairs.sum::<f64>() / (a.len() * b.len()) as f64,
+        Linkage::Ward => {
+            let (na, nb) = (a.len() as f64, b.len() as f64);
+            (2.0 * na * nb / (na + nb)).sqrt()
+                * euclidean(&centroid(points, a), &centroid(points, b))
+        }
+    }
+}
+
+/// Merge heights and the partition into every number of clusters,
+/// by repeatedly merging the closest pair of clusters
+fn brute_force(points: &[[f64; 3]], linkage: Linkage) -> (Vec<f64>, Vec<Vec<Vec<usize>>>) {
+    let mut clusters = (0..points.len()).map(|i| vec![i]).collect::<Vec<_>>();
+    let mut heights = Vec::new();
+    let mut partitions = vec![clusters.clone()];
+
+    while clusters.len() > 1 {
+        let mut best = (0, 1, f64::INFINITY);
+        for a in 0..clusters.len() {
+            for b in a + 1..clusters.len() {
+                let d = linkage_distance(points, &clusters[a], &clusters[b], linkage);
+                if d < best.2 {
+                    best = (a, b, d);
+                }
+            }
+        }
+
+        let merged = clusters.remove(best.1);
+        clusters[best.0].extend(merged);
+        clusters[best.0].sort_unstable();
+        heights.push(best.2);
+
+        let mut partition = clusters.clone();
+        partition.sort();
+        partitions.push(partition);
+    }
+    (heights, partitions)
+}
+
+#[test]
+fn test_hierarchical_matches_brute_force() {
+    for seed in 0..3 {
+        let points = random_points(40, seed);
+        let n = points.len();
+        let distances = Array2::from_shape_fn((n, n), |(i, j)| euclidean(&points[i], &points[j]));
+
+        for linkage in [
+            Linkage::Single,
+            Linkage::Complete,
+            Linkage::Average,
+            Linkage::Ward,
+        ] {
+            let dendrogram = hierarchical(distances.clone(), linkage);
+            let (heights, partitions) = brute_force(&points, linkage);
+
+            assert_eq!(dendrogram.merges.len(), n - 1);
+            for (merge, expected) in dendrogram.merges.iter().zip(&heights) {
+                assert!((merge.distance - expected).abs() < 1e-9, "{linkage:?}");
+            }
+
+            for (merges, expected) in partitions.iter().enumerate() {
+                let mut clusters = dendrogram.cut_k(n - merges).clusters;
+                clusters.sort();
+                assert_eq!(&clusters, expected, "{linkage:?}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_linkage_matrix() {
+    let points = random_points(25, 9);
+    let n = points.len();
+    let distances = Array2::from_shape_fn((n, n), |(i, j)| euclidean(&points[i], &points[j]));
+    let z = hierarchical(distances, Linkage::Average).linkage_matrix();
+
+    assert_eq!(z.dim(), (n - 1, 4));
+    assert_eq!(z[[n - 2, 3]], n as f64);
+    for (i, row) in z.rows().into_iter().enumerate() {
+        // Children are formed before their parent, smaller id first
+        assert!(row[0] < row[1]);
+        assert!(row[1] < (n + i) as f64);
+        if i > 0 {
+            assert!(row[2] >= z[[i - 1, 2]]);
+        }
+    }
+}
+
+#[test]
+fn test_fingerprints() {
+    let fps = random_fps(30, 64, 4);
+    let dendrogram = hierarchical(distance_matrix(&fps, soergel), Linkage::Complete);
+
+    // Complete linkage keeps every pair in a cluster within the cut distance
+    let result = dendrogram.cut_distance(0.7);
+    for cluster in &result.clusters {
+        for &i in cluster {
+            assert!(cluster.iter().all(|&j| soergel(&fps[i], &fps[j]) <= 0.7));
+        }
+    }
+    assert!(result.noise.is_empty());
+    assert_eq!(dendrogram.cut_k(1).clusters, [(0..30).collect::<Vec<_>>()]);
+    assert_eq!(dendrogram.cut_k(30).n_clusters(), 30);
+
+    let names = (0..30).map(|i| format!("m{i}")).collect::<Vec<_>>();
+    let newick = dendrogram.newick(&names);
+    assert!(newick.ends_with(';'));
+    assert_eq!(newick.matches('(').count(), 29);
+    assert_eq!(newick.matches(')').count(), 29);
+    assert!(names
+        .iter()
+        .all(|name| newick.contains(&format!("{name}:"))));
+}
+
+#[test]
+#[should_panic(expected = "one name per object")]
+fn test_newick_needs_every_name() {
+    let distances = ndarray::arr2(&[[0.0, 1.0, 2.0], [1.0, 0.0, 2.0], [2.0, 2.0, 0.0]]);
+    hierarchical(distances, Linkage::Average).newick(&["a", "b"]);
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use common::random_fps;
+use similarity_metrics::cluster::{jarvis_patrick, knn};
+use similarity_metrics::measures::{euclidean, soergel};
+
+#[test]
+fn test_knn_matches_full_sort() {
+    let fps = random_fps(80, 32, 0);
+    for metric in [soergel, euclidean] {
+        let lists = knn(&fps, 6, metric);
+        for (i, list) in lists.iter().enumerate() {
+            let mut all = (0..fps.len())
+                .filter(|&j| j != i)
+                .map(|j| (metric(&fps[i], &fps[j]), j))
+                .collect::<Vec<_>>();
+            all.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+            assert_eq!(list, &all[..6].iter().map(|&(_, j)| j).collect::<Vec<_>>());
+        }
+    }
+    assert_eq!(knn(&fps[..3], 6, soergel)[0].len(), 2);
+}
+
+/// Connected components of the shared-neighbour graph, found by depth-first search
+fn brute_force(lists: &[Vec<usize>], j: usize, shared: usize) -> Vec<Vec<usize>> {
+    let n = lists.len();
+    let near = |a: usize, b: usize| lists[a][..j].contains(&b);
+    let edge = |a: usize, b: usize| {
+        a != b
+            && near(a, b)
+            && near(b, a)
+            && lists[a][..j]
+                .iter()
+                .filter(|x| lists[b][..j].contains(x))
+                .count()
+                >= shared
+    };
+
+    let mut seen = vec![false; n];
+    let mut clusters = Vec::new();
+    for start in 0..n {
+        if seen[start] || !(0..n).any(|b| edge(start, b)) {
+            continue;
+        }
+        let mut cluster = vec![];
+        let mut stack = vec![start];
+        seen[start] = true;
+        while let Some(a) = stack.pop() {
+            cluster.push(a);
+            for b in (0..n).filter(|&b| edge(a, b)) {
+                if !seen[b] {
+                    seen[b] = true;
+                    stack.push(b);
+                }
+            }
+        }
+        cluster.sort_unstable();
+        clusters.push(cluster);
+    }
+    clusters
+}
+
+#[test]
+fn test_jarvis_patrick_matches_brute_force() {
+    for seed in 0..4 {
+        let fps = random_fps(120, 32, seed);
+        let lists = knn(&fps, 10, soergel);
+        for (j, shared) in [(5, 2), (8, 3), (10, 6)] {
+            let result = jarvis_patrick(&lists, j, shared);
+            assert_eq!(result.clusters, brute_force(&lists, j, shared));
+
+            let clustered = result.clusters.iter().map(Vec::len).sum::<usize>();
+            assert_eq!(clustered + result.noise.len(), fps.len());
+            assert!(result.noise.iter().all(|&i| result.labels[i].is_none()));
+        }
+    }
+}
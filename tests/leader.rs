@@ -0,0 +1,72 @@
+mod common;
+
+use common::random_fps;
+use similarity_metrics::cluster::{leader, Leaders};
+use similarity_metrics::measures::{euclidean, soergel};
+
+#[test]
+fn test_leader_properties() {
+    let fps = random_fps(300, 32, 0);
+    let result = leader(&fps, 0.6, soergel);
+
+    let mut leaders = Leaders::tanimoto(0.6);
+    leaders.add(&fps);
+    assert_eq!(result.n_clusters(), leaders.len());
+    assert_eq!(result.sizes(), leaders.sizes());
+
+    for (c, members) in result.clusters.iter().enumerate() {
+        let origin = leaders.origins()[c];
+        // The leader is the first member of its cluster
+        assert_eq!(members[0], origin);
+        assert!(members
+            .iter()
+            .all(|&i| soergel(&fps[i], &fps[origin]) <= 0.6));
+    }
+
+    let fps = leaders.leaders();
+    for i in 0..fps.len() {
+        assert!((i + 1..fps.len()).all(|j| soergel(&fps[i], &fps[j]) > 0.6));
+    }
+}
+
+#[test]
+fn test_incremental_matches_single_pass() {
+    let fps = random_fps(200, 32, 1);
+
+    let mut single = Leaders::new(2.5, euclidean);
+    let expected = single.add(&fps);
+
+    let mut batched = Leaders::new(2.5, euclidean);
+    let mut labels = Vec::new();
+    for batch in fps.chunks(37) {
+        labels.extend(batched.add(batch));
+    }
+    assert_eq!(labels, expected);
+    assert_eq!(batched.leaders(), single.leaders());
+    assert_eq!(batched.seen(), fps.len());
+}
+
+#[test]
+fn test_save_and_reload() {
+    let fps = random_fps(200, 32, 2);
+    let (first, second) = fps.split_at(120);
+
+    let mut uninterrupted = Leaders::tanimoto(0.5);
+    uninterrupted.add(first);
+    let expected = uninterrupted.add(second);
+
+    let mut leaders = Leaders::tanimoto(0.5);
+    leaders.add(first);
+    let mut saved = Vec::new();
+    leaders.save(&mut saved).unwrap();
+
+    let mut reloaded = Leaders::load(&saved[..], soergel).unwrap();
+    assert_eq!(reloaded.radius, 0.5);
+    assert_eq!(reloaded.seen(), 120);
+    assert_eq!(reloaded.add(second), expected);
+    assert_eq!(reloaded.origins(), uninterrupted.origins());
+    assert_eq!(reloaded.sizes(), uninterrupted.sizes());
+
+    assert!(Leaders::load(&b"radius\t0.5\nseen\t1\n0\t1\t01x\n"[..], soergel).is_err());
+    assert!(Leaders::load(&b"seen\t1\n"[..], soergel).is_err());
+}
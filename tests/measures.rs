@@ -0,0 +1,17 @@
+use similarity_metrics::measures::{cosine_distance, dice_distance, tanimoto_distance};
+
+/// Fingerprint of `len` bits with the bits in `set` on
+fn fp(len: usize, set: std::ops::Range<usize>) -> Vec<u8> {
+    (0..len).map(|i| set.contains(&i) as u8).collect()
+}
+
+#[test]
+fn test_distances_of_dense_fingerprints() {
+    // 600 bits on each, 300 of them shared
+    let a = fp(1024, 0..600);
+    let b = fp(1024, 300..900);
+    assert!((cosine_distance(&a, &b) - 0.5).abs() < 1e-12);
+    assert!((dice_distance(&a, &b) - 0.5).abs() < 1e-12);
+    assert!((tanimoto_distance(&a, &b) - 2.0 / 3.0).abs() < 1e-12);
+    assert_eq!(cosine_distance(&a, &a), 0.0);
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use common::{block_groups, random_fps};
+use similarity_metrics::cluster::{distance_matrix, fasterpam, KMedoids, Medoids};
+use similarity_metrics::measures::soergel;
+
+/// Groups of fingerprints that share most of their bits within the group
+fn groups(sizes: &[usize], seed: u64) -> Vec<Vec<u8>> {
+    block_groups(sizes, 16, 0, 0.85, seed)
+}
+
+fn cost(distances: &ndarray::Array2<f64>, medoids: &[usize]) -> f64 {
+    (0..distances.nrows())
+        .map(|o| {
+            medoids
+                .iter()
+                .map(|&m| distances[[o, m]])
+                .fold(f64::INFINITY, f64::min)
+        })
+        .sum()
+}
+
+#[test]
+fn test_fasterpam_is_swap_optimal() {
+    for seed in 0..4 {
+        let distances = distance_matrix(&random_fps(40, 48, seed), soergel);
+        let result = fasterpam(&distances, 4, seed);
+        let total = cost(&distances, &result.medoids);
+        assert!((result.cost - total).abs() < 1e-9);
+
+        // No single swap of a medoid with a non-medoid lowers the cost
+        for i in 0..result.medoids.len() {
+            for c in (0..40).filter(|c| !result.medoids.contains(c)) {
+                let mut swapped = result.medoids.clone();
+                swapped[i] = c;
+                assert!(cost(&distances, &swapped) >= total - 1e-9);
+            }
+        }
+
+        for (o, &label) in result.labels.iter().enumerate() {
+            let d = distances[[o, result.medoids[label]]];
+            assert!(result.medoids.iter().all(|&m| distances[[o, m]] >= d));
+        }
+    }
+}
+
+#[test]
+fn test_clara_finds_groups() {
+    let fps = groups(&[120, 80, 100], 1);
+    let clara = KMedoids {
+        max_matrix: 100,
+        ..KMedoids::new(3)
+    };
+    let result = clara.fit(&fps);
+
+    let mut medoids = result.medoids.clone();
+    medoids.sort_unstable();
+    medoids.dedup();
+    assert_eq!(medoids.len(), 3);
+    assert!(result.medoids.iter().any(|&m| m < 120));
+    assert!(result.medoids.iter().any(|&m| (120..200).contains(&m)));
+    assert!(result.medoids.iter().any(|&m| m >= 200));
+
+    let clusters = result.result().clusters;
+    let mut sizes = clusters.iter().map(Vec::len).collect::<Vec<_>>();
+    sizes.sort_unstable();
+    assert_eq!(sizes, [80, 100, 120]);
+
+    assert_eq!(clara.fit(&fps), result);
+}
+
+#[test]
+fn test_small_inputs() {
+    let fps = random_fps(3, 48, 2);
+    let result = KMedoids::new(3).fit(&fps);
+    let mut medoids = result.medoids.clone();
+    medoids.sort_unstable();
+    assert_eq!(medoids, [0, 1, 2]);
+    assert_eq!(result.cost, 0.0);
+
+    assert_eq!(KMedoids::new(5).fit(&fps), Medoids::default());
+    assert_eq!(KMedoids::new(0).fit(&fps), Medoids::default());
+    assert_eq!(
+        fasterpam(&distance_matrix(&fps, soergel), 0, 0),
+        Medoids::default()
+    );
+    assert!(KMedoids::new(2).fit(&[]).medoids.is_empty());
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use common::block_groups;
+use rand::{Rng, SeedableRng};
+use similarity_metrics::cluster::{
+    adjusted_rand_index, calinski_harabasz, davies_bouldin, normalized_mutual_info, silhouette,
+    silhouette_sampled,
+};
+use similarity_metrics::measures::{cosine_distance, euclidean, soergel, tanimoto_distance};
+
+/// Three groups of fingerprints with no bits in common, labelled by group
+fn groups() -> (Vec<Vec<u8>>, Vec<Option<usize>>) {
+    let fps = block_groups(&[15, 15, 15], 16, 8, 0.4, 11);
+    (fps, (0..45).map(|i| Some(i / 15)).collect())
+}
+
+fn brute_force_silhouette(
+    fps: &[Vec<u8>],
+    labels: &[Option<usize>],
+    metric: fn(&[u8], &[u8]) -> f64,
+) -> f64 {
+    let labelled = (0..fps.len())
+        .filter(|&i| labels[i].is_some())
+        .collect::<Vec<_>>();
+    let mut total = 0.0;
+    for &i in &labelled {
+        let mean_to = |c: Option<usize>| {
+            let others = labelled
+                .iter()
+                .filter(|&&j| j != i && labels[j] == c)
+                .map(|&j| metric(&fps[i], &fps[j]))
+                .collect::<Vec<_>>();
+            others.iter().sum::<f64>() / others.len() as f64
+        };
+        if labelled.iter().all(|&j| j == i || labels[j] != labels[i]) {
+            continue;
+        }
+        let a = mean_to(labels[i]);
+        let b = labelled
+            .iter()
+            .map(|&j| labels[j])
+            .filter(|&c| c != labels[i])
+            .map(mean_to)
+            .fold(f64::INFINITY, f64::min);
+        total += (b - a) / a.max(b);
+    }
+    total / labelled.len() as f64
+}
+
+#[test]
+fn test_silhouette() {
+    let (fps, mut labels) = groups();
+    labels[4] = None;
+    labels[10] = Some(7);
+    labels[11] = Some(1);
+
+    for metric in [soergel, euclidean, tanimoto_distance, cosine_distance] {
+        let exact = silhouette(&fps, &labels, metric);
+        assert!((exact - brute_force_silhouette(&fps, &labels, metric)).abs() < 1e-12);
+        assert!((silhouette_sampled(&fps, &labels, 100, 0, metric) - exact).abs() < 1e-12);
+    }
+
+    let exact = silhouette(&fps, &labels, soergel);
+    assert!((silhouette(&fps, &labels, tanimoto_distance) - exact).abs() < 1e-12);
+
+    let sampled = silhouette_sampled(&fps, &labels, 20, 3, soergel);
+    assert_eq!(sampled, silhouette_sampled(&fps, &labels, 20, 3, soergel));
+    assert!((sampled - silhouette(&fps, &labels, soergel)).abs() < 0.1);
+    assert!(silhouette(&fps, &groups().1, soergel) > 0.7);
+    assert!(silhouette(&fps, &[Some(0); 45], soergel).is_nan());
+    assert!(silhouette_sampled(&fps, &labels, 0, 0, soergel).is_nan());
+}
+
+#[test]
+#[should_panic(expected = "one label per fingerprint")]
+fn test_silhouette_needs_every_label() {
+    let (fps, labels) = groups();
+    silhouette(&fps, &labels[1..], soergel);
+}
+
+#[test]
+#[should_panic(expected = "one label per fingerprint")]
+fn test_davies_bouldin_needs_every_label() {
+    let (fps, labels) = groups();
+    davies_bouldin(&fps[1..], &labels, soergel);
+}
+
+#[test]
+fn test_internal_indices_prefer_true_groups() {
+    let (fps, labels) = groups();
+    let mut rng = rand::rngs::StdRng::seed_from_u64(12);
+    let shuffled = (0..fps.len())
+        .map(|_| Some(rng.gen_range(0..3)))
+        .collect::<Vec<_>>();
+
+    for metric in [soergel, euclidean] {
+        assert!(silhouette(&fps, &labels, metric) > silhouette(&fps, &shuffled, metric));
+        assert!(davies_bouldin(&fps, &labels, metric) < davies_bouldin(&fps, &shuffled, metric));
+        assert!(
+            calinski_harabasz(&fps, &labels, metric) > calinski_harabasz(&fps, &shuffled, metric)
+        );
+    }
+    assert!(davies_bouldin(&fps, &[None; 45], soergel).is_nan());
+    assert_eq!(
+        calinski_harabasz(&[vec![0, 1], vec![1, 0]], &[Some(0), Some(1)], soergel),
+        1.0
+    );
+}
+
+#[test]
+fn test_external_indices() {
+    let (_, labels) = groups();
+    let renamed = labels.iter().map(|l| l.map(|c| 10 - c)).collect::<Vec<_>>();
+    assert_eq!(adjusted_rand_index(&labels, &renamed), 1.0);
+    assert!((normalized_mutual_info(&labels, &renamed) - 1.0).abs() < 1e-12);
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(13);
+    let random = (0..2000)
+        .map(|_| Some(rng.gen_range(0..4)))
+        .collect::<Vec<_>>();
+    let other = (0..2000)
+        .map(|_| Some(rng.gen_range(0..4)))
+        .collect::<Vec<_>>();
+    assert!(adjusted_rand_index(&random, &other).abs() < 0.01);
+    assert!(normalized_mutual_info(&random, &other) < 0.01);
+
+    // Noise counts as a cluster of its own
+    let noisy = [None, None, Some(0), Some(0)];
+    assert_eq!(
+        adjusted_rand_index(&noisy, &[Some(3), Some(3), None, None]),
+        1.0
+    );
+    assert_eq!(adjusted_rand_index(&[Some(0)], &[None]), 1.0);
+    assert_eq!(normalized_mutual_info(&[], &[]), 1.0);
+    assert_eq!(
+        normalized_mutual_info(&[Some(0), Some(0)], &[Some(0), Some(1)]),
+        0.0
+    );
+}